@@ -16,7 +16,16 @@
 
 use super::*;
 
+#[cfg(feature = "std")]
+use core::cell::Cell;
+use core::sync::atomic::{AtomicU64, Ordering};
+use core::time::Duration;
+#[cfg(feature = "std")]
 use std::env;
+#[cfg(feature = "std")]
+use std::sync::OnceLock;
+#[cfg(feature = "std")]
+use std::time::Instant;
 
 /// The root of a console application.
 ///
@@ -34,12 +43,15 @@ use std::env;
 ///         .with_command(&app_main)
 ///         .build();  
 ///
+/// # #[cfg(feature = "std")]
 /// fn main() {
 ///     match EXAMPLE_APPLICATION.run() {
 ///         Ok(ret) => {},  // handle return value
 ///         Err(err) => {}, // handle command line error
 ///     }
 /// }
+/// # #[cfg(not(feature = "std"))]
+/// # fn main() {}
 ///
 /// fn app_main() {
 ///     println!("Hello, world!");
@@ -51,9 +63,47 @@ pub struct Application<'a, R = ()> {
     flags: &'a [Flag<'a>],
     params: &'a [Parameter<'a>],
     subcommands: &'a [SubCommand<'a, R>],
+    owned_flags: Vec<Flag<'a>>,
+    owned_params: Vec<Parameter<'a>>,
+    owned_subcommands: Vec<SubCommand<'a, R>>,
+    positionals: Option<&'a PositionalValue>,
+    required_one_of: &'a [&'a str],
     command: Option<Callback<'a, R>>,
-    help: bool,
-    // version: bool,
+    #[cfg(feature = "async")]
+    async_command: Option<AsyncCallback<'a, R>>,
+    help_command: bool,
+    help_flag: bool,
+    empty_args_help: bool,
+    require_subcommand: bool,
+    default_subcommand: Option<ExternalSubcommand<R>>,
+    unknown_as_positional: bool,
+    unknown_args: PositionalValue,
+    last_parse_duration: ParseDuration,
+    #[cfg(feature = "std")]
+    index: OnceLock<LookupIndex>,
+    after_help: Option<&'a str>,
+    version: Option<&'a str>,
+    help_labels: Option<&'a HelpLabels<'a>>,
+    pre_run: Option<fn()>,
+    binder: Option<Binder>,
+    #[cfg(feature = "std")]
+    exit_on_error: bool,
+    #[cfg(feature = "std")]
+    error_writer: Option<ErrorWriter>,
+    #[cfg(feature = "std")]
+    error_formatter: Option<ErrorFormatter>,
+    subcommand_flag: Option<&'a str>,
+    config_dump_flag: Option<&'a str>,
+    #[cfg(feature = "std")]
+    json_trace: Option<&'a JsonTraceWriter>,
+    value_sources: &'a [&'a (dyn ValueSource + Sync)],
+    #[cfg(feature = "std")]
+    use_argv0_name: bool,
+    /// The basename of the binary path, used as the name when [`with_use_argv0_name`](ApplicationBuilder::with_use_argv0_name)
+    /// is enabled.
+    #[cfg(feature = "std")]
+    argv0_name: OnceLock<String>,
+    settings: Settings,
 }
 
 impl<'a, R> Application<'a, R> {
@@ -64,28 +114,411 @@ impl<'a, R> Application<'a, R> {
             flags: None,
             params: None,
             subcommands: None,
+            owned_flags: Vec::new(),
+            owned_params: Vec::new(),
+            owned_subcommands: Vec::new(),
+            positionals: None,
+            required_one_of: &[],
             command: None,
-            help: true,
+            #[cfg(feature = "async")]
+            async_command: None,
+            help_command: true,
+            help_flag: true,
+            empty_args_help: false,
+            require_subcommand: false,
+            default_subcommand: None,
+            unknown_as_positional: false,
+            after_help: None,
+            version: None,
+            help_labels: None,
+            pre_run: None,
+            binder: None,
+            #[cfg(feature = "std")]
+            exit_on_error: false,
+            #[cfg(feature = "std")]
+            error_writer: None,
+            #[cfg(feature = "std")]
+            error_formatter: None,
+            subcommand_flag: None,
+            config_dump_flag: None,
+            #[cfg(feature = "std")]
+            json_trace: None,
+            value_sources: &[],
+            #[cfg(feature = "std")]
+            use_argv0_name: false,
+            settings: Settings::new(),
         }
     }
 
     /// Parses the command line arguments from [std::env::args()](std::env::args) and dispatched to the appropriate command.
+    ///
+    /// Note: the first argument (the binary path) is skipped, the same as
+    /// [run_from_env_skip_binary()](Self::run_from_env_skip_binary).
+    #[cfg(feature = "std")]
     pub fn run(&self) -> Result<R, CommandLineError> {
-        let mut args = env::args();
-        let _binary = args
-            .next()
-            .expect("Expected path to binary as first argument");
-        self.execute(args)
+        self.run_from_env_skip_binary()
+    }
+
+    /// Parses the command line arguments from [std::env::args()](std::env::args), skipping the first argument (the
+    /// binary path), and dispatches to the appropriate command.
+    ///
+    /// If help is requested it is printed and the process exits.
+    #[cfg(feature = "std")]
+    pub fn run_from_env_skip_binary(&self) -> Result<R, CommandLineError> {
+        self.run_from(self.skip_argv0(env::args()))
+    }
+
+    /// Parses already split arguments and dispatches to the appropriate command.
+    ///
+    /// Note: nothing is skipped, so `args` must not start with the binary path. Use
+    /// [run_from_env_skip_binary()](Self::run_from_env_skip_binary) to parse the arguments of the process.
+    ///
+    /// If help is requested it is printed and the process exits.
+    #[cfg(feature = "std")]
+    pub fn run_from(&self, args: impl IntoIterator<Item = String>) -> Result<R, CommandLineError> {
+        self.execute(args.into_iter())
     }
 
     /// Parses the provided command line arguments and dispatched to the appropriate command.
     ///
     /// Note: this function does not skip the first argument (the binary path) that [run()](Self::run) does.
+    ///
+    /// If help is requested it is printed and the process exits. An error does the same, with
+    /// [`exit_code()`](CommandLineError::exit_code), if [exit on error](ApplicationBuilder::with_exit_on_error) is
+    /// enabled.
+    #[cfg(feature = "std")]
     pub fn execute<T: AsRef<str>>(
         &self,
         args: impl Iterator<Item = T>,
     ) -> Result<R, CommandLineError> {
-        Executable::execute(self, args)
+        let (mut stdout, mut stderr) = (std::io::stdout(), std::io::stderr());
+        match self.execute_with(args, &mut self.std_context(&mut stdout, &mut stderr)) {
+            Ok(RunOutcome::Completed(ret)) => Ok(ret),
+            Ok(RunOutcome::HelpShown | RunOutcome::VersionShown | RunOutcome::ConfigPrinted) => {
+                std::process::exit(0)
+            }
+            Err(error) if self.exit_on_error => {
+                self.print_error(&error);
+                std::process::exit(error.exit_code())
+            }
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Splits `line` into arguments, honoring quotes and backslash escapes, and dispatches to the appropriate command.
+    ///
+    /// Unterminated quotes are reported as [`CommandLineError::ParseError`].
+    ///
+    /// If help is requested it is printed and the process exits.
+    #[cfg(feature = "std")]
+    pub fn execute_str(&self, line: &str) -> Result<R, CommandLineError> {
+        self.execute(tokenize::tokenize(line)?.iter())
+    }
+
+    /// Parses the provided command line arguments and dispatched to the appropriate command.
+    ///
+    /// Unlike [execute()](Self::execute), this function never exits the process: if help is requested it is printed and
+    /// [`RunOutcome::HelpShown`] is returned.
+    #[cfg(feature = "std")]
+    pub fn execute_no_exit<T: AsRef<str>>(
+        &self,
+        args: impl Iterator<Item = T>,
+    ) -> Result<RunOutcome<R>, CommandLineError> {
+        let (mut stdout, mut stderr) = (std::io::stdout(), std::io::stderr());
+        self.execute_with(args, &mut self.std_context(&mut stdout, &mut stderr))
+    }
+
+    /// Parses the provided command line arguments and dispatched to the appropriate command, like
+    /// [execute()](Self::execute), also returning the long names of the subcommands that were descended into
+    /// (e.g. `["remote", "add"]`).
+    #[cfg(feature = "std")]
+    pub fn execute_tracked<T: AsRef<str>>(
+        &self,
+        args: impl Iterator<Item = T>,
+    ) -> Result<(R, Vec<String>), CommandLineError> {
+        let (mut stdout, mut stderr) = (std::io::stdout(), std::io::stderr());
+        let mut ctx = self.std_context(&mut stdout, &mut stderr);
+        match self.execute_with(args, &mut ctx)? {
+            RunOutcome::Completed(ret) => Ok((ret, ctx.path)),
+            RunOutcome::HelpShown | RunOutcome::VersionShown | RunOutcome::ConfigPrinted => {
+                std::process::exit(0)
+            }
+        }
+    }
+
+    /// Parses the provided command line arguments and dispatched to the appropriate command, like
+    /// [execute()](Self::execute), also returning how many arguments were consumed, including parameter values.
+    ///
+    /// Useful when `args` is part of a larger stream: every argument read before dispatching is counted.
+    #[cfg(feature = "std")]
+    pub fn execute_counting<T: AsRef<str>>(
+        &self,
+        args: impl Iterator<Item = T>,
+    ) -> Result<(R, usize), CommandLineError> {
+        let consumed = Cell::new(0);
+        let args = args.inspect(|_| consumed.set(consumed.get() + 1));
+        let (mut stdout, mut stderr) = (std::io::stdout(), std::io::stderr());
+        match self.execute_with(args, &mut self.std_context(&mut stdout, &mut stderr))? {
+            RunOutcome::Completed(ret) => Ok((ret, consumed.get())),
+            RunOutcome::HelpShown | RunOutcome::VersionShown | RunOutcome::ConfigPrinted => {
+                std::process::exit(0)
+            }
+        }
+    }
+
+    /// Parses the command line arguments from [std::env::args()](std::env::args) and awaits the appropriate command.
+    #[cfg(feature = "async")]
+    pub async fn run_async(&self) -> Result<R, CommandLineError> {
+        self.execute_async(self.skip_argv0(env::args())).await
+    }
+
+    /// Parses the provided command line arguments and awaits the appropriate command.
+    ///
+    /// Commands without an async callback fall back to their synchronous callback.
+    ///
+    /// Note: this function does not skip the first argument (the binary path) that [run_async()](Self::run_async) does.
+    #[cfg(feature = "async")]
+    pub async fn execute_async<T: AsRef<str>>(
+        &self,
+        args: impl Iterator<Item = T>,
+    ) -> Result<R, CommandLineError> {
+        let (mut stdout, mut stderr) = (std::io::stdout(), std::io::stderr());
+        match self.parse_with(args, &mut self.std_context(&mut stdout, &mut stderr))? {
+            ParseOutcome::Dispatch(command) => {
+                self.pre_run();
+                Ok(dispatch_async(command).await)
+            }
+            ParseOutcome::External(handler, name, args) => {
+                self.pre_run();
+                Ok(handler(&name, &args))
+            }
+            ParseOutcome::HelpShown | ParseOutcome::VersionShown | ParseOutcome::ConfigPrinted => {
+                std::process::exit(0)
+            }
+        }
+    }
+
+    /// Parses the provided command line arguments and dispatches to the appropriate command, writing help and
+    /// warnings to `out` and `err` instead of the standard streams.
+    ///
+    /// This never touches the process (no exit, no environment, no terminal), if help is requested
+    /// [`RunOutcome::HelpShown`] is returned. It is available without the `std` feature, writing to any
+    /// [`io::Write`](crate::constexpr::io::Write) such as a `Vec<u8>`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use cliutil::constexpr::{Application, RunOutcome};
+    ///
+    /// static APPLICATION: Application = Application::build()
+    ///     .with_name("app")
+    ///     .with_command(&|| {})
+    ///     .build();
+    ///
+    /// let (mut out, mut err) = (Vec::new(), Vec::new());
+    /// let outcome = APPLICATION.execute_into(["--help"].iter(), &mut out, &mut err);
+    /// assert!(matches!(outcome, Ok(RunOutcome::HelpShown)));
+    /// assert!(String::from_utf8(out).unwrap().starts_with("app\n"));
+    /// ```
+    pub fn execute_into<T: AsRef<str>>(
+        &self,
+        args: impl Iterator<Item = T>,
+        out: &mut dyn io::Write,
+        err: &mut dyn io::Write,
+    ) -> Result<RunOutcome<R>, CommandLineError> {
+        self.execute_with(args, &mut self.context(out, err))
+    }
+
+    /// Parses the flags, parameters and positionals of the application itself into `slots`, without allocating on
+    /// success, returning how many slots were filled (e.g. for memory constrained targets).
+    ///
+    /// Each slot borrows its value from `args`. Nothing is stored in the flag and parameter values and no command
+    /// runs: the caller reads the slots and dispatches. More arguments than slots fail with
+    /// [`CommandLineError::ParseError`].
+    ///
+    /// Only the application's own flags and parameters are known: subcommands are never descended into, so a
+    /// subcommand name is recorded as an [`ArgSlot::Positional`] and the options of the subcommand after it fail with
+    /// [`CommandLineError::UnknownArgument`]. Clusters (`-fg`), configuration and environment fallbacks are not
+    /// supported either.
+    pub fn parse_into_slots<'s>(
+        &'s self,
+        args: &[&'s str],
+        slots: &mut [ArgSlot<'s>],
+    ) -> Result<usize, CommandLineError> {
+        slots::parse_into_slots(self, args, slots)
+    }
+
+    /// Prints `error` to stderr, or the [error writer](ApplicationBuilder::with_error_writer) if there is one, as an
+    /// `error: ...` line, in color if the [color choice](ApplicationBuilder::with_color) allows it.
+    #[cfg(feature = "std")]
+    pub fn print_error(&self, error: &CommandLineError) {
+        if let Some(error_writer) = self.error_writer {
+            // an error writer is never taken for a terminal
+            let color = self.settings.color.enabled(false, terminal::no_color());
+            let _ = report::write_error(&mut *error_writer(), error, self.error_formatter, color);
+            return;
+        }
+        let color = self.settings.color.enabled(
+            terminal::is_terminal(terminal::Stream::Stderr),
+            terminal::no_color(),
+        );
+        let _ = report::write_error(
+            &mut std::io::stderr().lock(),
+            error,
+            self.error_formatter,
+            color,
+        );
+    }
+
+    /// Writes the completion script of the application for `shell`, completing its flags, parameters and subcommand
+    /// names. Parameter values are completed from their allowed values or
+    /// [value hint](crate::constexpr::builders::ParameterBuilder::with_value_hint) (e.g. `_files` in zsh).
+    ///
+    /// The script is named after the application, which needs a name. Options of subcommands are not completed.
+    pub fn write_completion(&self, shell: Shell, w: &mut dyn io::Write) -> io::Result<()> {
+        completion::write_completion(self, shell, w)
+    }
+
+    /// Renders the completion script of the application for `shell`, as written by
+    /// [`write_completion`](Self::write_completion).
+    pub fn render_completion(&self, shell: Shell) -> String {
+        completion::render_completion(self, shell)
+    }
+
+    /// Renders the full help of the application, as printed by `--help`.
+    pub fn render_help(&self) -> String {
+        help::render_help(self, &self.settings)
+    }
+
+    /// Writes only the subcommands sections of the help (names and descriptions, grouped by category), e.g. for a
+    /// dispatcher with its own top-level interface.
+    pub fn write_subcommands(&self, w: &mut dyn io::Write) -> io::Result<()> {
+        help::write_subcommand_list(self, w, &self.settings)
+    }
+
+    /// Prints only the subcommands sections of the help to stdout, as listed when a required subcommand is missing.
+    #[cfg(feature = "std")]
+    pub fn print_subcommands(&self) {
+        let _ = self.write_subcommands(&mut std::io::stdout());
+    }
+
+    /// Renders the full help of the application laid out as `options` say, e.g. to embed it in another interface.
+    pub fn format_help(&self, options: HelpOptions) -> String {
+        help::format_help(self, &options)
+    }
+
+    /// The width of the name column of compact help, where descriptions start.
+    pub const fn help_name_width(&self) -> usize {
+        help::options(&self.settings).name_width()
+    }
+
+    /// The width of the description column of compact help for the [help width](ApplicationBuilder::with_help_width),
+    /// descriptions are truncated to it.
+    pub const fn help_description_width(&self) -> usize {
+        help::options(&self.settings).description_width()
+    }
+
+    /// Creates the context for a parse starting at this application, writing to the standard streams.
+    #[cfg(feature = "std")]
+    fn std_context<'c>(
+        &'c self,
+        stdout: &'c mut std::io::Stdout,
+        stderr: &'c mut std::io::Stderr,
+    ) -> Context<'c> {
+        let pager = self.settings.pager && terminal::is_terminal(terminal::Stream::Stdout);
+        let mut ctx = self.context(stdout, stderr);
+        ctx.pager = pager;
+        ctx
+    }
+
+    /// Creates the context for a parse starting at this application.
+    pub(super) fn context<'c>(
+        &'c self,
+        out: &'c mut dyn io::Write,
+        err: &'c mut dyn io::Write,
+    ) -> Context<'c> {
+        let unknown_args = self.settings.ignore_unknown.then_some(&self.unknown_args);
+        // without `std` there is no lock to build the index once with, arguments are looked up linearly
+        #[cfg(not(feature = "std"))]
+        let index = None;
+        #[cfg(feature = "std")]
+        let index = self.settings.indexed_lookup.then(|| {
+            self.index.get_or_init(|| {
+                LookupIndex::new(
+                    self.flags(),
+                    self.parameters(),
+                    self.subcommands(),
+                    self.settings.max_depth,
+                )
+            })
+        });
+        let mut ctx = Context::new(
+            out,
+            err,
+            &self.settings,
+            unknown_args,
+            index,
+            self.subcommand_flag,
+            self.config_dump_flag,
+        );
+        #[cfg(feature = "std")]
+        {
+            ctx.json_trace = self.json_trace;
+        }
+        ctx.value_sources = self.value_sources;
+        ctx
+    }
+
+    /// Parses `args` with `ctx`, recording how long it took if timing is enabled and tracing a failure.
+    pub(super) fn parse_with<T: AsRef<str>>(
+        &self,
+        args: impl Iterator<Item = T>,
+        ctx: &mut Context,
+    ) -> Result<ParseOutcome<'_, R>, CommandLineError> {
+        #[cfg(feature = "std")]
+        let start = self.settings.timing.then(Instant::now);
+        let result = Executable::parse(self, args, ctx, &[]);
+        #[cfg(feature = "std")]
+        if let Some(start) = start {
+            self.last_parse_duration.set(start.elapsed());
+        }
+        match &result {
+            Ok(ParseOutcome::Dispatch(_) | ParseOutcome::External(..)) => {
+                if let Some(binder) = self.binder {
+                    values::bind(self, &ctx.path, binder)
+                }
+            }
+            Ok(_) => {}
+            Err(err) => ctx.trace(&ParseEvent::Error(err)),
+        }
+        result
+    }
+
+    /// Parses `args` with `ctx` and dispatches to the selected command.
+    pub(super) fn execute_with<T: AsRef<str>>(
+        &self,
+        args: impl Iterator<Item = T>,
+        ctx: &mut Context,
+    ) -> Result<RunOutcome<R>, CommandLineError> {
+        match self.parse_with(args, ctx)? {
+            ParseOutcome::Dispatch(command) => {
+                self.pre_run();
+                Ok(RunOutcome::Completed(dispatch(command)))
+            }
+            ParseOutcome::External(handler, name, args) => {
+                self.pre_run();
+                Ok(RunOutcome::Completed(handler(&name, &args)))
+            }
+            ParseOutcome::HelpShown => Ok(RunOutcome::HelpShown),
+            ParseOutcome::VersionShown => Ok(RunOutcome::VersionShown),
+            ParseOutcome::ConfigPrinted => Ok(RunOutcome::ConfigPrinted),
+        }
+    }
+
+    /// Runs the pre-run hook, if there is one.
+    fn pre_run(&self) {
+        if let Some(pre_run) = self.pre_run {
+            pre_run()
+        }
     }
 
     pub const fn name(&self) -> &str {
@@ -96,16 +529,142 @@ impl<'a, R> Application<'a, R> {
         self.description
     }
 
-    pub const fn flags(&self) -> &[Flag] {
-        self.flags
+    pub const fn flags(&self) -> &[Flag<'_>] {
+        if self.owned_flags.is_empty() {
+            self.flags
+        } else {
+            self.owned_flags.as_slice()
+        }
+    }
+
+    pub const fn parameters(&self) -> &[Parameter<'_>] {
+        if self.owned_params.is_empty() {
+            self.params
+        } else {
+            self.owned_params.as_slice()
+        }
+    }
+
+    pub const fn subcommands(&self) -> &[SubCommand<'_, R>] {
+        if self.owned_subcommands.is_empty() {
+            self.subcommands
+        } else {
+            self.owned_subcommands.as_slice()
+        }
+    }
+
+    /// Panics unless the application has a command to run or a subcommand to select.
+    const fn assert_runnable(&self) {
+        #[cfg(feature = "async")]
+        let has_command = self.command.is_some() || self.async_command.is_some();
+        #[cfg(not(feature = "async"))]
+        let has_command = self.command.is_some();
+        assert!(
+            has_command || !self.subcommands().is_empty(),
+            "Application must have either a default command or at least one subcommand."
+        );
+    }
+
+    pub const fn positionals(&self) -> Option<&PositionalValue> {
+        self.positionals
+    }
+
+    /// The long names of the subcommands, in order, without their aliases.
+    pub fn subcommand_names(&self) -> Vec<&str> {
+        self.subcommands()
+            .iter()
+            .map(SubCommand::long_name)
+            .collect()
+    }
+
+    /// The long names of the flags, in order, leaving out flags that only have a short name.
+    pub fn flag_long_names(&self) -> Vec<&str> {
+        long_names(self.flags())
+    }
+
+    /// The long names of the parameters, in order, leaving out parameters that only have a short name.
+    pub fn parameter_long_names(&self) -> Vec<&str> {
+        long_names(self.parameters())
+    }
+
+    /// The unknown flags and parameters collected when [`with_ignore_unknown`](ApplicationBuilder::with_ignore_unknown) is enabled.
+    pub fn unknown_args(&self) -> &[String] {
+        self.unknown_args.values()
+    }
+
+    /// Reads the current value of every flag and parameter, keyed by long name (or short name if it has none).
+    ///
+    /// Positional arguments, if collected, are included under [`POSITIONALS_KEY`].
+    pub fn collect_values(&self) -> Map<String, ArgValue> {
+        values::collect_values(self)
+    }
+
+    /// Describes the current value of every flag and parameter for debugging, one per line (e.g. `verbose: false`
+    /// or `output: None`).
+    pub fn debug_state(&self) -> String {
+        values::debug_state(self)
+    }
+
+    /// How long the last parse took, if [`with_timing`](ApplicationBuilder::with_timing) is enabled.
+    pub fn last_parse_duration(&self) -> Option<Duration> {
+        self.last_parse_duration.get()
+    }
+
+    /// Advances `args` past the binary path, keeping its basename as the name if
+    /// [`with_use_argv0_name`](ApplicationBuilder::with_use_argv0_name) is enabled and no name is configured.
+    #[cfg(feature = "std")]
+    pub(super) fn skip_argv0<I: Iterator<Item = String>>(&self, args: I) -> I {
+        let (binary, args) = split_binary(args);
+        if self.use_argv0_name && self.name.is_empty() {
+            if let Some(name) = std::path::Path::new(&binary).file_name() {
+                let _ = self.argv0_name.set(name.to_string_lossy().into_owned());
+            }
+        }
+        args
+    }
+
+    /// The name shown in help, the basename of the binary path if it replaces an empty configured name.
+    fn program_name(&self) -> &str {
+        #[cfg(feature = "std")]
+        if let Some(name) = self.argv0_name.get().filter(|_| self.use_argv0_name) {
+            return name;
+        }
+        self.name
+    }
+}
+
+/// Splits the binary path, which the operating system passes as the first argument, from the rest of `args`.
+#[cfg(feature = "std")]
+fn split_binary<I: Iterator>(mut args: I) -> (I::Item, I) {
+    let binary = args
+        .next()
+        .expect("Expected path to binary as first argument");
+    (binary, args)
+}
+
+/// The duration of the last parse, in nanoseconds, shared by every thread parsing with the application.
+struct ParseDuration(AtomicU64);
+
+impl ParseDuration {
+    /// Stored before any parse has been timed.
+    const NONE: u64 = u64::MAX;
+
+    const fn new() -> Self {
+        Self(AtomicU64::new(Self::NONE))
     }
 
-    pub const fn parameters(&self) -> &[Parameter] {
-        self.params
+    #[cfg(feature = "std")]
+    fn set(&self, duration: Duration) {
+        // durations too long to count in nanoseconds are saturated, never mistaken for no duration
+        let nanos = u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX);
+        self.0.store(nanos.min(Self::NONE - 1), Ordering::Relaxed)
     }
 
-    pub const fn subcommands(&self) -> &[SubCommand<R>] {
-        self.subcommands
+    fn get(&self) -> Option<Duration> {
+        match self.0.load(Ordering::Relaxed) {
+            Self::NONE => None,
+            nanos => Some(Duration::from_nanos(nanos)),
+        }
     }
 }
 
@@ -115,8 +674,39 @@ pub struct ApplicationBuilder<'a, R> {
     flags: Option<&'a [Flag<'a>]>,
     params: Option<&'a [Parameter<'a>]>,
     subcommands: Option<&'a [SubCommand<'a, R>]>,
+    owned_flags: Vec<Flag<'a>>,
+    owned_params: Vec<Parameter<'a>>,
+    owned_subcommands: Vec<SubCommand<'a, R>>,
+    positionals: Option<&'a PositionalValue>,
+    required_one_of: &'a [&'a str],
     command: Option<Callback<'a, R>>,
-    help: bool,
+    #[cfg(feature = "async")]
+    async_command: Option<AsyncCallback<'a, R>>,
+    help_command: bool,
+    help_flag: bool,
+    empty_args_help: bool,
+    require_subcommand: bool,
+    default_subcommand: Option<ExternalSubcommand<R>>,
+    unknown_as_positional: bool,
+    after_help: Option<&'a str>,
+    version: Option<&'a str>,
+    help_labels: Option<&'a HelpLabels<'a>>,
+    pre_run: Option<fn()>,
+    binder: Option<Binder>,
+    #[cfg(feature = "std")]
+    exit_on_error: bool,
+    #[cfg(feature = "std")]
+    error_writer: Option<ErrorWriter>,
+    #[cfg(feature = "std")]
+    error_formatter: Option<ErrorFormatter>,
+    subcommand_flag: Option<&'a str>,
+    config_dump_flag: Option<&'a str>,
+    #[cfg(feature = "std")]
+    json_trace: Option<&'a JsonTraceWriter>,
+    value_sources: &'a [&'a (dyn ValueSource + Sync)],
+    #[cfg(feature = "std")]
+    use_argv0_name: bool,
+    settings: Settings,
 }
 
 impl<'a, R> ApplicationBuilder<'a, R> {
@@ -145,17 +735,371 @@ impl<'a, R> ApplicationBuilder<'a, R> {
         self
     }
 
+    /// Sets flags assembled at runtime, owned by the application. These take precedence over
+    /// [`with_flags`](Self::with_flags).
+    pub fn with_owned_flags(mut self, flags: Vec<Flag<'a>>) -> Self {
+        self.owned_flags = flags;
+        self
+    }
+
+    /// Sets parameters assembled at runtime, owned by the application. These take precedence over
+    /// [`with_parameters`](Self::with_parameters).
+    pub fn with_owned_parameters(mut self, params: Vec<Parameter<'a>>) -> Self {
+        self.owned_params = params;
+        self
+    }
+
+    /// Sets subcommands assembled at runtime, owned by the application. These take precedence over
+    /// [`with_subcommands`](Self::with_subcommands).
+    pub fn with_owned_subcommands(mut self, subcommands: Vec<SubCommand<'a, R>>) -> Self {
+        self.owned_subcommands = subcommands;
+        self
+    }
+
+    /// Collects arguments that are not flags, parameters or subcommands into `positionals`.
+    pub const fn with_positionals(mut self, positionals: &'a PositionalValue) -> Self {
+        self.positionals = Some(positionals);
+        self
+    }
+
+    /// Requires exactly one of the flags or parameters with the long names `group` to be given (e.g. `--stdin`,
+    /// `--file` or `--url`), reported as [`CommandLineError::MissingOneOf`] or [`CommandLineError::ConflictingFlags`].
+    ///
+    /// Only the command line counts, a member with a default or configured value is not given.
+    pub const fn with_required_one_of(mut self, group: &'a [&'a str]) -> Self {
+        self.required_one_of = group;
+        self
+    }
+
     pub const fn with_command(mut self, command: Callback<'a, R>) -> Self {
         self.command = Some(command);
         self
     }
 
+    /// Sets a callback returning a future that is awaited when dispatched asynchronously.
+    #[cfg(feature = "async")]
+    pub const fn with_async_command(mut self, command: AsyncCallback<'a, R>) -> Self {
+        self.async_command = Some(command);
+        self
+    }
+
+    /// Enables or disables both the `help` command keyword and the `-h`/`--help` flag.
     pub const fn with_help(mut self, enabled: bool) -> Self {
-        self.help = enabled;
+        self.help_command = enabled;
+        self.help_flag = enabled;
+        self
+    }
+
+    /// Enables or disables only the built-in `help` command keyword.
+    pub const fn with_help_command(mut self, enabled: bool) -> Self {
+        self.help_command = enabled;
         self
     }
 
+    /// Overrides the section titles used in help.
+    pub const fn with_help_labels(mut self, labels: &'a HelpLabels<'a>) -> Self {
+        self.help_labels = Some(labels);
+        self
+    }
+
+    /// Lets `--{name}=example` select the `example` subcommand, the same as giving `example` on its own.
+    ///
+    /// This applies to every level of subcommands (e.g. `--command=remote --command=add`).
+    pub const fn with_subcommand_flag(mut self, name: &'a str) -> Self {
+        self.subcommand_flag = Some(name);
+        self
+    }
+
+    /// Lets `--{name}` (e.g. `--print-config`) print every flag and parameter once they are resolved, including
+    /// configured values, instead of running the selected command.
+    ///
+    /// This applies to every level of subcommands, and the arguments of every level passed through are printed.
+    pub const fn with_config_dump_flag(mut self, name: &'a str) -> Self {
+        self.config_dump_flag = Some(name);
+        self
+    }
+
+    /// When no name is configured, shows the basename of the binary path (e.g. `mytool` for `/usr/bin/mytool`) as the
+    /// name in help, for multi-call binaries.
+    ///
+    /// The binary path is taken where it is skipped, by [`run`](Application::run),
+    /// [`run_from_env_skip_binary`](Application::run_from_env_skip_binary) and `run_async`.
+    #[cfg(feature = "std")]
+    pub const fn with_use_argv0_name(mut self, enabled: bool) -> Self {
+        self.use_argv0_name = enabled;
+        self
+    }
+
+    /// Sets a hook run once parsing has completed, immediately before the selected command's callback.
+    ///
+    /// The hook runs after descending into every subcommand on the command line, so all of their flags and
+    /// parameters are set, and only when a callback is invoked (not when help is shown or parsing fails).
+    pub const fn with_pre_run(mut self, pre_run: fn()) -> Self {
+        self.pre_run = Some(pre_run);
+        self
+    }
+
+    /// Prints a command line error with [`print_error`](Application::print_error) and exits with its
+    /// [exit code](CommandLineError::exit_code) from [`run()`](Application::run) (and [`execute()`](Application::execute),
+    /// which it calls), instead of returning it.
+    #[cfg(feature = "std")]
+    pub const fn with_exit_on_error(mut self, enabled: bool) -> Self {
+        self.exit_on_error = enabled;
+        self
+    }
+
+    /// Sets where [`print_error`](Application::print_error) writes errors, instead of stderr (e.g. a log file).
+    #[cfg(feature = "std")]
+    pub const fn with_error_writer(mut self, error_writer: ErrorWriter) -> Self {
+        self.error_writer = Some(error_writer);
+        self
+    }
+
+    /// Sets a callback receiving the value of every flag and parameter, by long name (or short name if it has none),
+    /// once parsing has succeeded and before the command runs (e.g. to fill a user struct without a derive).
+    ///
+    /// Only the application and the subcommands on the command line are bound, parents first.
+    pub const fn with_binder(mut self, binder: Binder) -> Self {
+        self.binder = Some(binder);
+        self
+    }
+
+    /// Sets how [`print_error`](Application::print_error) describes errors (e.g. in French), in place of their English
+    /// [`Display`](core::fmt::Display) text. The `error:` label and coloring are kept.
+    #[cfg(feature = "std")]
+    pub const fn with_error_formatter(mut self, formatter: ErrorFormatter) -> Self {
+        self.error_formatter = Some(formatter);
+        self
+    }
+
+    /// Sets text printed verbatim at the end of help, after all sections.
+    pub const fn with_after_help(mut self, text: &'a str) -> Self {
+        self.after_help = Some(text);
+        self
+    }
+
+    /// Sets the version printed by `--version` (e.g. `myapp 1.2.0`), also inherited by subcommands without their own.
+    pub const fn with_version(mut self, version: &'a str) -> Self {
+        self.version = Some(version);
+        self
+    }
+
+    /// Enables or disables only the built-in `-h`/`--help` flag.
+    pub const fn with_help_flag(mut self, enabled: bool) -> Self {
+        self.help_flag = enabled;
+        self
+    }
+
+    /// Prints help, instead of erroring, when an application without a default command is run with no arguments.
+    pub const fn with_empty_args_help(mut self, enabled: bool) -> Self {
+        self.empty_args_help = enabled;
+        self
+    }
+
+    /// Lists the available subcommands on stderr before failing with [`CommandLineError::ExpectedSubcommand`], when an
+    /// application without a default command is run without a subcommand.
+    pub const fn with_require_subcommand(mut self, enabled: bool) -> Self {
+        self.require_subcommand = enabled;
+        self
+    }
+
+    /// Hands a command that matches no subcommand to `handler`, with the remaining arguments, instead of failing with
+    /// [`CommandLineError::UnknownCommand`] (e.g. to run `myapp-foo` for `myapp foo`, the way git does).
+    ///
+    /// Leftover arguments are still collected as positionals when the application has them.
+    pub const fn with_default_subcommand(mut self, handler: ExternalSubcommand<R>) -> Self {
+        self.default_subcommand = Some(handler);
+        self
+    }
+
+    /// Stops parsing at the first argument that matches no subcommand: it and every argument after it, options and
+    /// subcommand names included, are collected as positionals for the default command (e.g. `myapp foo --bar build`
+    /// collects `["foo", "--bar", "build"]`).
+    ///
+    /// Such an argument is a positional either way, this only changes the arguments after it, which are otherwise
+    /// still parsed (`--bar` would be an unknown argument and `build` would select its subcommand). Only applies
+    /// when the application has both a default command and positionals.
+    pub const fn with_unknown_as_positional(mut self, enabled: bool) -> Self {
+        self.unknown_as_positional = enabled;
+        self
+    }
+
+    /// Shows help in a pager when it is printed to a terminal: `$PAGER` if it is set, otherwise `less -R`,
+    /// falling back to `more`.
+    ///
+    /// If no pager can be started, or the output is not a terminal, help is printed directly.
+    pub const fn with_pager(mut self, pager: bool) -> Self {
+        self.settings.pager = pager;
+        self
+    }
+
+    /// Sets when [`print_error`](Application::print_error) uses color (defaults to [`ColorChoice::Auto`]).
+    pub const fn with_color(mut self, choice: ColorChoice) -> Self {
+        self.settings.color = choice;
+        self
+    }
+
+    /// Sets the layout used when printing help for the application and its subcommands.
+    pub const fn with_help_style(mut self, style: HelpStyle) -> Self {
+        self.settings.help_style = style;
+        self
+    }
+
+    /// Sets the console width help is wrapped and truncated to (defaults to 80 columns).
+    pub const fn with_help_width(mut self, width: usize) -> Self {
+        self.settings.help_width = width;
+        self
+    }
+
+    /// Allows flags to be given an explicit boolean (e.g. `--verbose=false`), one of `true`, `1` and `yes` or `false`,
+    /// `0` and `no`. Anything else fails with [`CommandLineError::InvalidValue`].
+    ///
+    /// Otherwise a flag given a value fails with [`CommandLineError::FlagTakesNoValue`].
+    pub const fn with_flag_values(mut self, enabled: bool) -> Self {
+        self.settings.flag_values = enabled;
+        self
+    }
+
+    /// Allows flags to be enabled with `+flag` and disabled with `-flag`, in addition to `--flag`.
+    pub const fn with_plus_minus_flags(mut self, enabled: bool) -> Self {
+        self.settings.plus_minus_flags = enabled;
+        self
+    }
+
+    /// Only accepts the values of long parameters attached with `=` (`--output=file`), so `--output file` fails with
+    /// [`CommandLineError::MissingEquals`] suggesting that form instead of taking `file`.
+    ///
+    /// Short parameters (`-o file`) still take the following argument.
+    pub const fn with_require_equals(mut self, enabled: bool) -> Self {
+        self.settings.require_equals = enabled;
+        self
+    }
+
+    /// Accepts Windows style options: `/example` and `/example:value` are read as `--example` and `--example=value`.
+    ///
+    /// Only names of flags, parameters and help are taken as options, so a path such as `/tmp` is still an argument.
+    pub const fn with_slash_options(mut self, enabled: bool) -> Self {
+        self.settings.slash_options = enabled;
+        self
+    }
+
+    /// Collects unknown flags and parameters, at every level, into [`unknown_args`](Application::unknown_args)
+    /// instead of erroring.
+    pub const fn with_ignore_unknown(mut self, enabled: bool) -> Self {
+        self.settings.ignore_unknown = enabled;
+        self
+    }
+
+    /// Sets a callback deciding, per token, what to do with arguments that match no flag or parameter.
+    pub const fn with_unknown_handler(mut self, handler: UnknownHandler) -> Self {
+        self.settings.unknown_handler = Some(handler);
+        self
+    }
+
+    /// Matches subcommand names and aliases regardless of (ASCII) case.
+    pub const fn with_case_insensitive(mut self, enabled: bool) -> Self {
+        self.settings.case_insensitive = enabled;
+        self
+    }
+
+    /// Sets a callback notified of every flag, parameter and subcommand as it is parsed, and of a failure.
+    pub const fn with_trace(mut self, trace: TraceCallback) -> Self {
+        self.settings.trace = Some(trace);
+        self
+    }
+
+    /// Writes every parse event to `w` as a JSON object on its own line (e.g.
+    /// `{"event":"FlagMarked","name":"verbose"}`), a greppable record of how the arguments were interpreted.
+    ///
+    /// The writer sits behind a mutex so the application can still be a `static`. Write errors are ignored.
+    #[cfg(feature = "std")]
+    pub const fn with_json_trace(mut self, w: &'a JsonTraceWriter) -> Self {
+        self.json_trace = Some(w);
+        self
+    }
+
+    /// Records how long parsing takes, exposed by [`last_parse_duration`](Application::last_parse_duration).
+    ///
+    /// Without the `std` feature there is no clock, and nothing is recorded.
+    pub const fn with_timing(mut self, enabled: bool) -> Self {
+        self.settings.timing = enabled;
+        self
+    }
+
+    /// Sets a lookup of configured parameter values (e.g. from a config file), queried by long name for every
+    /// parameter not given on the command line. Configured values are validated like any other.
+    pub const fn with_config_source(mut self, config_source: ConfigSource) -> Self {
+        self.settings.config_source = Some(config_source);
+        self
+    }
+
+    /// Sets layers of configured parameter values (e.g. [`EnvSource`] over [`DefaultSource`]), queried in order by
+    /// long name for every parameter not given on the command line until one has a value.
+    ///
+    /// They take precedence over the [config source](Self::with_config_source), a parameter's own
+    /// [environment variable](crate::constexpr::builders::ParameterBuilder::with_env) takes precedence over them.
+    pub const fn with_value_sources(mut self, sources: &'a [&'a (dyn ValueSource + Sync)]) -> Self {
+        self.value_sources = sources;
+        self
+    }
+
+    /// When a required parameter is missing and standard input is a terminal, prompts for its value on stderr
+    /// instead of failing with [`CommandLineError::MissingRequiredParameter`].
+    ///
+    /// Without the `std` feature there is no terminal to prompt on, and missing parameters always fail.
+    pub const fn with_interactive_prompts(mut self, enabled: bool) -> Self {
+        self.settings.interactive_prompts = enabled;
+        self
+    }
+
+    /// Sets how many levels of subcommands a parse may descend into (defaults to 64), deeper ones fail with
+    /// [`CommandLineError::MaxDepthExceeded`].
+    pub const fn with_max_depth(mut self, depth: usize) -> Self {
+        self.settings.max_depth = depth;
+        self
+    }
+
+    /// Resolves arguments through name indexes, built on first use, instead of scanning linearly.
+    ///
+    /// This is only worthwhile for commands with many flags or parameters. Without the `std` feature arguments are
+    /// always scanned linearly.
+    pub const fn with_indexed_lookup(mut self, enabled: bool) -> Self {
+        self.settings.indexed_lookup = enabled;
+        self
+    }
+
+    /// Builds the application. Use [`build_owned`](Self::build_owned) if it has owned flags, parameters or
+    /// subcommands.
     pub const fn build(self) -> Application<'a, R> {
+        assert!(
+            self.owned_flags.is_empty()
+                && self.owned_params.is_empty()
+                && self.owned_subcommands.is_empty(),
+            "Application with owned flags, parameters or subcommands must be built with build_owned."
+        );
+        let app = self.assemble();
+        app.assert_runnable();
+        app
+    }
+
+    /// Builds the application, moving in the lists set with the `with_owned_*` methods (e.g.
+    /// [`with_owned_flags`](Self::with_owned_flags)). Unlike [`build`](Self::build), it cannot be used in a const.
+    pub fn build_owned(mut self) -> Application<'a, R> {
+        let owned_flags = core::mem::take(&mut self.owned_flags);
+        let owned_params = core::mem::take(&mut self.owned_params);
+        let owned_subcommands = core::mem::take(&mut self.owned_subcommands);
+        let app = Application {
+            owned_flags,
+            owned_params,
+            owned_subcommands,
+            ..self.assemble()
+        };
+        app.assert_runnable();
+        app
+    }
+
+    /// Copies the settings of the builder into a application with no owned lists, which must already have been taken.
+    const fn assemble(self) -> Application<'a, R> {
         let app = Application {
             name: match self.name {
                 Some(name) => name,
@@ -177,43 +1121,141 @@ impl<'a, R> ApplicationBuilder<'a, R> {
                 Some(subcommands) => subcommands,
                 None => &[],
             },
+            owned_flags: Vec::new(),
+            owned_params: Vec::new(),
+            owned_subcommands: Vec::new(),
+            positionals: self.positionals,
+            required_one_of: self.required_one_of,
             command: self.command,
-            help: self.help,
+            #[cfg(feature = "async")]
+            async_command: self.async_command,
+            help_command: self.help_command,
+            help_flag: self.help_flag,
+            empty_args_help: self.empty_args_help,
+            require_subcommand: self.require_subcommand,
+            default_subcommand: self.default_subcommand,
+            unknown_as_positional: self.unknown_as_positional,
+            unknown_args: PositionalValue::new(),
+            last_parse_duration: ParseDuration::new(),
+            #[cfg(feature = "std")]
+            index: OnceLock::new(),
+            after_help: self.after_help,
+            version: self.version,
+            help_labels: self.help_labels,
+            pre_run: self.pre_run,
+            binder: self.binder,
+            #[cfg(feature = "std")]
+            exit_on_error: self.exit_on_error,
+            #[cfg(feature = "std")]
+            error_writer: self.error_writer,
+            #[cfg(feature = "std")]
+            error_formatter: self.error_formatter,
+            subcommand_flag: self.subcommand_flag,
+            config_dump_flag: self.config_dump_flag,
+            #[cfg(feature = "std")]
+            json_trace: self.json_trace,
+            value_sources: self.value_sources,
+            #[cfg(feature = "std")]
+            use_argv0_name: self.use_argv0_name,
+            #[cfg(feature = "std")]
+            argv0_name: OnceLock::new(),
+            settings: self.settings,
         };
-        assert!(
-            app.command.is_some() || !app.subcommands.is_empty(),
-            "Application must have either a default command or at least one subcommand."
-        );
+        // the owned lists are empty, so forgetting the builder, which cannot be dropped in a const fn, leaks nothing
+        core::mem::forget(self);
         app
     }
+
+    /// Builds the application, reporting a misconfiguration as a [`BuildError`] instead of panicking.
+    ///
+    /// Unlike [`build`](Self::build), flags, parameters or subcommands sharing a name are also reported.
+    pub fn try_build(self) -> Result<Application<'a, R>, BuildError> {
+        #[cfg(feature = "async")]
+        let has_command = self.command.is_some() || self.async_command.is_some();
+        #[cfg(not(feature = "async"))]
+        let has_command = self.command.is_some();
+        let has_subcommands =
+            self.subcommands.is_some_and(|s| !s.is_empty()) || !self.owned_subcommands.is_empty();
+        if !has_command && !has_subcommands {
+            return Err(BuildError::NoCommandOrSubcommand);
+        }
+        let app = self.build_owned();
+        check_unique_names(app.flags(), app.parameters(), app.subcommands())?;
+        Ok(app)
+    }
 }
 
 impl<R> Command<R> for Application<'_, R> {
     fn name(&self) -> &str {
-        self.name
+        self.program_name()
     }
 
     fn description(&self) -> &str {
         self.description
     }
 
-    fn flags(&self) -> &[Flag] {
-        self.flags
+    fn flags(&self) -> &[Flag<'_>] {
+        self.flags()
+    }
+
+    fn parameters(&self) -> &[Parameter<'_>] {
+        self.parameters()
     }
 
-    fn parameters(&self) -> &[Parameter] {
-        self.params
+    fn subcommands(&self) -> &[SubCommand<'_, R>] {
+        self.subcommands()
     }
 
-    fn subcommands(&self) -> &[SubCommand<R>] {
-        self.subcommands
+    fn positionals(&self) -> Option<&PositionalValue> {
+        self.positionals
     }
 
-    fn command(&self) -> Option<Callback<R>> {
+    fn required_one_of(&self) -> &[&str] {
+        self.required_one_of
+    }
+
+    fn command(&self) -> Option<Callback<'_, R>> {
         self.command
     }
 
-    fn help_enabled(&self) -> bool {
-        self.help
+    #[cfg(feature = "async")]
+    fn async_command(&self) -> Option<AsyncCallback<'_, R>> {
+        self.async_command
+    }
+
+    fn help_command_enabled(&self) -> bool {
+        self.help_command
+    }
+
+    fn help_flag_enabled(&self) -> bool {
+        self.help_flag
+    }
+
+    fn after_help(&self) -> Option<&str> {
+        self.after_help
+    }
+
+    fn version(&self) -> Option<&str> {
+        self.version
+    }
+
+    fn help_labels(&self) -> Option<&HelpLabels<'_>> {
+        self.help_labels
+    }
+
+    fn empty_args_help(&self) -> bool {
+        self.empty_args_help
+    }
+
+    fn require_subcommand(&self) -> bool {
+        self.require_subcommand
+    }
+
+    fn default_subcommand(&self) -> Option<ExternalSubcommand<R>> {
+        self.default_subcommand
+    }
+
+    fn unknown_as_positional(&self) -> bool {
+        self.unknown_as_positional
     }
 }
@@ -0,0 +1,247 @@
+/*
+ * Copyright 2023 Jacob R. Green
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::*;
+
+use super::io::{self, Write};
+
+/// A shell a completion script can be generated for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+/// Writes the completion script of a command for `shell`, completing its flags, parameters and subcommand names.
+///
+/// Parameter values are completed from their allowed values or their [`ValueHint`].
+pub(super) fn write_completion<R, C: Command<R> + ?Sized>(
+    command: &C,
+    shell: Shell,
+    w: &mut dyn Write,
+) -> io::Result<()> {
+    match shell {
+        Shell::Bash => write_bash(command, w),
+        Shell::Zsh => write_zsh(command, w),
+        Shell::Fish => write_fish(command, w),
+    }
+}
+
+/// The completion script of a command for `shell`.
+pub(super) fn render_completion<R, C: Command<R> + ?Sized>(command: &C, shell: Shell) -> String {
+    let mut script = Vec::new();
+    write_completion(command, shell, &mut script).expect("Writing to a Vec cannot fail.");
+    String::from_utf8(script).expect("Completion scripts are always valid UTF-8.")
+}
+
+/// The parameters of a command that can be given on the command line.
+fn visible_parameters<'c>(
+    parameters: &'c [Parameter<'c>],
+) -> impl Iterator<Item = &'c Parameter<'c>> {
+    parameters.iter().filter(|param| param.is_cli_visible())
+}
+
+/// Every name an argument is written as (e.g. `--verbose`, `--loud` and `-v`).
+fn option_names<A: Argument>(a: &A) -> Vec<String> {
+    let mut names = Vec::new();
+    if !a.long_name().is_empty() {
+        names.push(format!("--{}", a.long_name()));
+    }
+    names.extend(a.aliases().iter().map(|alias| format!("--{}", alias)));
+    if !a.short_name().is_empty() {
+        names.push(format!("-{}", a.short_name()));
+    }
+    names
+}
+
+/// The name of a command as a shell function name (e.g. `my_app` for `my-app`).
+fn function_name(name: &str) -> String {
+    name.chars()
+        .map(|ch| if ch.is_ascii_alphanumeric() { ch } else { '_' })
+        .collect()
+}
+
+/// Quotes `text` as a single argument in a POSIX shell.
+fn single_quoted(text: &str) -> String {
+    format!("'{}'", text.replace('\'', r"'\''"))
+}
+
+fn write_bash<R, C: Command<R> + ?Sized>(command: &C, w: &mut dyn Write) -> io::Result<()> {
+    let function = function_name(command.name());
+
+    writeln!(w, "_{}() {{", function)?;
+    writeln!(w, "    local cur=\"${{COMP_WORDS[COMP_CWORD]}}\"")?;
+    writeln!(w, "    local prev=\"${{COMP_WORDS[COMP_CWORD-1]}}\"")?;
+
+    let params: Vec<_> = visible_parameters(command.parameters()).collect();
+    if !params.is_empty() {
+        writeln!(w, "    case \"$prev\" in")?;
+        for param in &params {
+            let reply = match (param.allowed_values(), param.value_hint()) {
+                ([], ValueHint::FilePath) => "compgen -f -- \"$cur\"".to_string(),
+                ([], ValueHint::DirPath) => "compgen -d -- \"$cur\"".to_string(),
+                ([], ValueHint::Hostname) => "compgen -A hostname -- \"$cur\"".to_string(),
+                ([], ValueHint::Other) => String::new(),
+                (values, _) => format!(
+                    "compgen -W {} -- \"$cur\"",
+                    single_quoted(&values.join(" "))
+                ),
+            };
+            writeln!(w, "        {})", option_names(*param).join("|"))?;
+            match reply.is_empty() {
+                true => writeln!(w, "            COMPREPLY=()")?,
+                false => writeln!(w, "            COMPREPLY=($({}))", reply)?,
+            }
+            writeln!(w, "            return 0")?;
+            writeln!(w, "            ;;")?;
+        }
+        writeln!(w, "    esac")?;
+    }
+
+    let mut words: Vec<String> = command.flags().iter().flat_map(option_names).collect();
+    words.extend(params.iter().flat_map(|param| option_names(*param)));
+    for subcommand in command.subcommands() {
+        words.push(subcommand.long_name().to_string());
+        words.extend(subcommand.aliases().iter().map(|alias| alias.to_string()));
+    }
+    writeln!(
+        w,
+        "    COMPREPLY=($(compgen -W {} -- \"$cur\"))",
+        single_quoted(&words.join(" "))
+    )?;
+    writeln!(w, "}}")?;
+    writeln!(w, "complete -F _{} {}", function, command.name())
+}
+
+/// Escapes `text` for the brackets of a zsh `_arguments` spec, inside single quotes.
+fn zsh_escaped(text: &str) -> String {
+    text.replace('\'', r"'\''")
+        .replace('[', r"\[")
+        .replace(']', r"\]")
+        .replace(':', r"\:")
+}
+
+fn write_zsh<R, C: Command<R> + ?Sized>(command: &C, w: &mut dyn Write) -> io::Result<()> {
+    let function = function_name(command.name());
+
+    writeln!(w, "#compdef {}", command.name())?;
+    writeln!(w)?;
+    writeln!(w, "_{}() {{", function)?;
+    writeln!(w, "    _arguments \\")?;
+
+    let mut specs = Vec::new();
+    for flag in command.flags() {
+        let description = zsh_escaped(flag.description());
+        for name in option_names(flag) {
+            specs.push(format!("'{}[{}]'", name, description));
+        }
+    }
+    for param in visible_parameters(command.parameters()) {
+        let description = zsh_escaped(param.description());
+        let action = match (param.allowed_values(), param.value_hint()) {
+            ([], ValueHint::FilePath) => "_files".to_string(),
+            ([], ValueHint::DirPath) => "_directories".to_string(),
+            ([], ValueHint::Hostname) => "_hosts".to_string(),
+            ([], ValueHint::Other) => " ".to_string(),
+            (values, _) => format!("({})", zsh_escaped(&values.join(" "))),
+        };
+        for name in option_names(param) {
+            // `=` takes the value after `=` or as the next word, `+` right after the name or as the next word
+            let separator = match name.starts_with("--") {
+                true => '=',
+                false => '+',
+            };
+            specs.push(format!(
+                "'{}{}[{}]:{}:{}'",
+                name,
+                separator,
+                description,
+                zsh_escaped(param.value_name()),
+                action
+            ));
+        }
+    }
+    if !command.subcommands().is_empty() {
+        let names: Vec<&str> = command
+            .subcommands()
+            .iter()
+            .map(|subcommand| subcommand.long_name())
+            .collect();
+        specs.push(format!(
+            "'1:subcommand:({})'",
+            zsh_escaped(&names.join(" "))
+        ));
+    }
+
+    for spec in &specs {
+        writeln!(w, "        {} \\", spec)?;
+    }
+    writeln!(w, "        '*:: :_default'")?;
+    writeln!(w, "}}")?;
+    writeln!(w)?;
+    writeln!(w, "_{} \"$@\"", function)
+}
+
+/// Quotes `text` as a single argument in fish.
+fn fish_quoted(text: &str) -> String {
+    format!("'{}'", text.replace('\\', r"\\").replace('\'', r"\'"))
+}
+
+/// The `complete` options naming an argument (e.g. `-s v -l verbose`).
+fn fish_names<A: Argument>(a: &A) -> String {
+    let mut names = String::new();
+    if !a.short_name().is_empty() {
+        names.push_str(&format!(" -s {}", a.short_name()));
+    }
+    if !a.long_name().is_empty() {
+        names.push_str(&format!(" -l {}", a.long_name()));
+    }
+    for alias in a.aliases() {
+        names.push_str(&format!(" -l {}", alias));
+    }
+    names
+}
+
+fn write_fish<R, C: Command<R> + ?Sized>(command: &C, w: &mut dyn Write) -> io::Result<()> {
+    let name = command.name();
+
+    for flag in command.flags() {
+        write!(w, "complete -c {}{}", name, fish_names(flag))?;
+        writeln!(w, " -d {}", fish_quoted(flag.description()))?;
+    }
+    for param in visible_parameters(command.parameters()) {
+        let values = match (param.allowed_values(), param.value_hint()) {
+            ([], ValueHint::FilePath) => " -F".to_string(),
+            ([], ValueHint::DirPath) => " -f -a '(__fish_complete_directories)'".to_string(),
+            ([], ValueHint::Hostname) => " -f -a '(__fish_print_hostnames)'".to_string(),
+            ([], ValueHint::Other) => " -f".to_string(),
+            (values, _) => format!(" -f -a {}", fish_quoted(&values.join(" "))),
+        };
+        write!(w, "complete -c {}{} -r{}", name, fish_names(param), values)?;
+        writeln!(w, " -d {}", fish_quoted(param.description()))?;
+    }
+    for subcommand in command.subcommands() {
+        writeln!(
+            w,
+            "complete -c {} -n __fish_use_subcommand -f -a {} -d {}",
+            name,
+            subcommand.long_name(),
+            fish_quoted(subcommand.description())
+        )?;
+    }
+    Ok(())
+}
@@ -0,0 +1,112 @@
+/*
+ * Copyright 2023 Jacob R. Green
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Tests of the engine alone, needing only `core` and `alloc`, so they also run without the `std` feature.
+
+use super::*;
+
+#[cfg(not(feature = "std"))]
+use alloc::vec;
+
+fn default_command() {}
+
+#[test]
+fn core_execute_into() {
+    let verbose = FlagValue::new();
+    let output = ParameterValue::new();
+    let positionals = PositionalValue::new();
+
+    let flags = &[Flag::build()
+        .with_long_name("verbose")
+        .with_short_name("v")
+        .with_description("Prints more output")
+        .with_flag(&verbose)
+        .build()];
+    let parameters = &[Parameter::build()
+        .with_long_name("output")
+        .with_description("The output file")
+        .with_parameter(&output)
+        .build()];
+
+    let app: Application = Application::build()
+        .with_name("app")
+        .with_flags(flags)
+        .with_parameters(parameters)
+        .with_positionals(&positionals)
+        .with_command(&default_command)
+        .build();
+
+    let (mut out, mut err) = (Vec::new(), Vec::new());
+    let result = app.execute_into(
+        ["-v", "--output", "out.txt", "input"].iter(),
+        &mut out,
+        &mut err,
+    );
+    assert!(matches!(result, Ok(RunOutcome::Completed(()))));
+    assert!(verbose.value());
+    assert_eq!(output.value(), Some("out.txt"));
+    assert_eq!(positionals.values(), ["input"]);
+    assert!(out.is_empty() && err.is_empty());
+
+    // help goes to the given output, never to a process stream
+    let result = app.execute_into(["--help"].iter(), &mut out, &mut err);
+    assert!(matches!(result, Ok(RunOutcome::HelpShown)));
+    let help = String::from_utf8(out).unwrap();
+    assert!(help.starts_with("app\n"));
+    assert!(help.contains("--verbose"));
+    assert_eq!(help, app.render_help());
+
+    let (mut out, mut err) = (Vec::new(), Vec::new());
+    let result = app.execute_into(["--unknown"].iter(), &mut out, &mut err);
+    assert!(matches!(result, Err(CommandLineError::UnknownArgument(arg)) if arg == "--unknown"));
+}
+
+#[test]
+fn core_io_write() {
+    /// A writer that stops accepting bytes once it holds `capacity` of them.
+    struct Limited {
+        bytes: Vec<u8>,
+        capacity: usize,
+    }
+
+    impl io::Write for Limited {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            let written = buf.len().min(self.capacity - self.bytes.len());
+            self.bytes.extend_from_slice(&buf[..written]);
+            Ok(written)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    use io::Write as _;
+
+    let mut bytes = vec![b'>'];
+    let (number, name) = (1, "one");
+    write!(bytes, " {}-{}", number, name).unwrap();
+    writeln!(&mut bytes).unwrap();
+    assert_eq!(bytes, b"> 1-one\n");
+
+    let mut limited = Limited {
+        bytes: Vec::new(),
+        capacity: 4,
+    };
+    let text = "too long";
+    assert!(write!(limited, "{}", text).is_err());
+    assert_eq!(limited.bytes, b"too ");
+}
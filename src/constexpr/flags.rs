@@ -14,17 +14,19 @@
  * limitations under the License.
  */
 
-use crate::constexpr::Argument;
+use crate::constexpr::{Argument, BuildError};
+
+use core::any::Any;
 
 /// The value that will be set when a flag is present on the command line.
 pub struct FlagValue {
-    value: std::cell::Cell<bool>,
+    value: core::cell::Cell<bool>,
 }
 
 impl FlagValue {
     pub const fn new() -> Self {
         Self {
-            value: std::cell::Cell::new(false),
+            value: core::cell::Cell::new(false),
         }
     }
 
@@ -32,13 +34,81 @@ impl FlagValue {
         self.value.get()
     }
 
-    fn mark(&self) {
-        self.value.set(true)
+    /// Sets whether the flag is considered present.
+    pub fn set(&self, value: bool) {
+        self.value.set(value)
+    }
+}
+
+impl Default for FlagValue {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 unsafe impl Sync for FlagValue {}
 
+/// A state shared by several flags, set to the value of whichever of them was given last.
+///
+/// # Example
+/// ```bash
+/// $ ./myapp --debug --release   # the mode is `Release`
+/// ```
+pub struct ModeValue<T> {
+    value: core::cell::Cell<Option<T>>,
+}
+
+impl<T: Copy> ModeValue<T> {
+    pub const fn new() -> Self {
+        Self {
+            value: core::cell::Cell::new(None),
+        }
+    }
+
+    /// The value of the last mode flag given, if any.
+    pub fn value(&self) -> Option<T> {
+        self.value.get()
+    }
+}
+
+impl<T: Copy> Default for ModeValue<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+unsafe impl<T: Send> Sync for ModeValue<T> {}
+
+/// A [`ModeValue`] with its type erased, so flags of any mode type can be stored together.
+trait Mode {
+    fn select(&self, value: &dyn Any);
+    fn deselect(&self, value: &dyn Any);
+    fn is_selected(&self, value: &dyn Any) -> bool;
+}
+
+impl<T: Copy + PartialEq + 'static> Mode for ModeValue<T> {
+    fn select(&self, value: &dyn Any) {
+        self.value.set(value.downcast_ref::<T>().copied())
+    }
+
+    fn deselect(&self, value: &dyn Any) {
+        if self.is_selected(value) {
+            self.value.set(None)
+        }
+    }
+
+    fn is_selected(&self, value: &dyn Any) -> bool {
+        self.value.get().as_ref() == value.downcast_ref::<T>()
+    }
+}
+
+/// What a flag sets when it is present.
+#[derive(Clone, Copy)]
+enum FlagTarget<'a> {
+    Flag(&'a FlagValue),
+    Mode(&'a (dyn Mode + Sync), &'a (dyn Any + Sync)),
+}
+
 /// A command line boolean flag.
 ///
 /// # Example
@@ -48,8 +118,15 @@ unsafe impl Sync for FlagValue {}
 pub struct Flag<'a> {
     short_name: &'a str,
     long_name: &'a str,
+    aliases: &'a [&'a str],
     description: &'a str,
-    flag: &'a FlagValue,
+    long_help: Option<&'a str>,
+    deprecated: bool,
+    deprecation_message: Option<&'a str>,
+    requires: &'a [&'a str],
+    on_set: Option<fn()>,
+    toggle: bool,
+    flag: FlagTarget<'a>,
 }
 
 impl<'a> Flag<'a> {
@@ -57,13 +134,31 @@ impl<'a> Flag<'a> {
         FlagBuilder {
             short_name: None,
             long_name: None,
+            aliases: &[],
             description: None,
+            long_help: None,
+            deprecated: false,
+            deprecation_message: None,
+            requires: &[],
+            on_set: None,
+            toggle: false,
             flag: None,
         }
     }
 
-    pub(crate) fn mark(&self) {
-        self.flag.mark();
+    pub(crate) fn set(&self, value: bool) {
+        match self.flag {
+            FlagTarget::Flag(flag) => flag.set(value),
+            FlagTarget::Mode(mode, mode_value) if value => mode.select(mode_value),
+            FlagTarget::Mode(mode, mode_value) => mode.deselect(mode_value),
+        }
+    }
+
+    /// Invokes the [`with_on_set`](FlagBuilder::with_on_set) callback, if there is one.
+    pub(crate) fn notify_set(&self) {
+        if let Some(on_set) = self.on_set {
+            on_set()
+        }
     }
 
     pub const fn short_name(&self) -> &str {
@@ -74,9 +169,43 @@ impl<'a> Flag<'a> {
         self.long_name
     }
 
+    pub const fn aliases(&self) -> &[&str] {
+        self.aliases
+    }
+
     pub const fn description(&self) -> &str {
         self.description
     }
+
+    pub const fn long_help(&self) -> Option<&str> {
+        self.long_help
+    }
+
+    pub const fn is_toggle(&self) -> bool {
+        self.toggle
+    }
+}
+
+/// Panics if two of `flags` set the same [`FlagValue`], which usually means one was wired to the wrong value.
+#[cfg(debug_assertions)]
+pub(crate) fn assert_distinct_values(flags: &[Flag]) {
+    for (index, flag) in flags.iter().enumerate() {
+        for other in &flags[index + 1..] {
+            let shared = match (flag.flag, other.flag) {
+                (FlagTarget::Flag(value), FlagTarget::Flag(other_value)) => {
+                    core::ptr::eq(value, other_value)
+                }
+                // mode flags share their mode by design
+                _ => false,
+            };
+            assert!(
+                !shared,
+                "Flags {} and {} share the same FlagValue.",
+                flag.display_name(),
+                other.display_name()
+            );
+        }
+    }
 }
 
 impl Argument for Flag<'_> {
@@ -88,16 +217,50 @@ impl Argument for Flag<'_> {
         self.short_name
     }
 
+    fn aliases(&self) -> &[&str] {
+        self.aliases
+    }
+
     fn description(&self) -> &str {
         self.description
     }
+
+    fn long_help(&self) -> Option<&str> {
+        self.long_help
+    }
+
+    fn is_deprecated(&self) -> bool {
+        self.deprecated
+    }
+
+    fn deprecation_message(&self) -> Option<&str> {
+        self.deprecation_message
+    }
+
+    fn requires(&self) -> &[&str] {
+        self.requires
+    }
+
+    fn is_present(&self) -> bool {
+        match self.flag {
+            FlagTarget::Flag(flag) => flag.value(),
+            FlagTarget::Mode(mode, mode_value) => mode.is_selected(mode_value),
+        }
+    }
 }
 
 pub struct FlagBuilder<'a> {
     short_name: Option<&'a str>,
     long_name: Option<&'a str>,
+    aliases: &'a [&'a str],
     description: Option<&'a str>,
-    flag: Option<&'a FlagValue>,
+    long_help: Option<&'a str>,
+    deprecated: bool,
+    deprecation_message: Option<&'a str>,
+    requires: &'a [&'a str],
+    on_set: Option<fn()>,
+    toggle: bool,
+    flag: Option<FlagTarget<'a>>,
 }
 
 impl<'a> FlagBuilder<'a> {
@@ -111,13 +274,71 @@ impl<'a> FlagBuilder<'a> {
         self
     }
 
+    /// Sets other long names the flag is accepted by (e.g. the legacy `colour` for `color`).
+    ///
+    /// Help shows the long name, the detailed style also lists the aliases.
+    pub const fn with_aliases(mut self, aliases: &'a [&'a str]) -> Self {
+        self.aliases = aliases;
+        self
+    }
+
     pub const fn with_description(mut self, description: &'a str) -> Self {
         self.description = Some(description);
         self
     }
 
+    /// Sets an extended description, printed instead of the description by the detailed help style.
+    pub const fn with_long_help(mut self, text: &'a str) -> Self {
+        self.long_help = Some(text);
+        self
+    }
+
+    /// Marks the flag as deprecated. It still works, but using it prints a warning
+    /// (including `message`, if any) to stderr.
+    pub const fn with_deprecated(mut self, message: Option<&'a str>) -> Self {
+        self.deprecated = true;
+        self.deprecation_message = message;
+        self
+    }
+
+    /// Sets the long names of flags or parameters that must also be present when this flag is.
+    pub const fn with_requires(mut self, requires: &'a [&'a str]) -> Self {
+        self.requires = requires;
+        self
+    }
+
+    /// Sets a callback invoked during parsing, the moment the flag is set, before any command runs
+    /// (e.g. to enable logging early).
+    ///
+    /// It fires once per occurrence, in the order the arguments are given, and not for a disabled flag (`-flag`).
+    pub const fn with_on_set(mut self, on_set: fn()) -> Self {
+        self.on_set = Some(on_set);
+        self
+    }
+
+    /// Makes every occurrence flip the flag instead of setting it, so `-t -t` leaves it as it was (e.g. a default
+    /// given with [`FlagValue::set`] before parsing).
+    ///
+    /// Explicit values (`--flag=false`) and disabled flags (`-flag`) still set it.
+    pub const fn with_toggle(mut self, toggle: bool) -> Self {
+        self.toggle = toggle;
+        self
+    }
+
     pub const fn with_flag(mut self, flag: &'a FlagValue) -> Self {
-        self.flag = Some(flag);
+        self.flag = Some(FlagTarget::Flag(flag));
+        self
+    }
+
+    /// Sets `mode` to `value` when the flag is present, instead of setting a [`FlagValue`].
+    ///
+    /// Several flags can share one mode, the last one given wins.
+    pub const fn with_mode_flag<T: Copy + PartialEq + Send + Sync + 'static>(
+        mut self,
+        mode: &'a ModeValue<T>,
+        value: &'a T,
+    ) -> Self {
+        self.flag = Some(FlagTarget::Mode(mode, value));
         self
     }
 
@@ -131,10 +352,17 @@ impl<'a> FlagBuilder<'a> {
                 Some(long_name) => long_name,
                 None => "",
             },
+            aliases: self.aliases,
             description: match self.description {
                 Some(description) => description,
                 None => "",
             },
+            long_help: self.long_help,
+            deprecated: self.deprecated,
+            deprecation_message: self.deprecation_message,
+            requires: self.requires,
+            on_set: self.on_set,
+            toggle: self.toggle,
             flag: match self.flag {
                 Some(flag) => flag,
                 None => panic!("Flag must have a flag value."),
@@ -146,4 +374,15 @@ impl<'a> FlagBuilder<'a> {
         );
         flag
     }
+
+    /// Builds the flag, reporting a misconfiguration as a [`BuildError`] instead of panicking.
+    pub fn try_build(self) -> Result<Flag<'a>, BuildError> {
+        if self.flag.is_none() {
+            return Err(BuildError::MissingValue);
+        }
+        if self.short_name.unwrap_or("").is_empty() && self.long_name.unwrap_or("").is_empty() {
+            return Err(BuildError::MissingName);
+        }
+        Ok(self.build())
+    }
 }
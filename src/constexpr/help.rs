@@ -0,0 +1,584 @@
+/*
+ * Copyright 2023 Jacob R. Green
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::*;
+
+use super::io::{self, Write};
+
+pub(super) const CONSOLE_WIOTH: usize = 80;
+pub(super) const NAME_WIDTH: usize = 20;
+const DETAILED_INDENT: usize = 10;
+
+/// The layout used when printing help.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HelpStyle {
+    /// One line per argument, with descriptions truncated to fit the description column.
+    Compact,
+    /// Each argument's name on its own line, followed by its full wrapped description and details.
+    Detailed,
+}
+
+/// The titles of the sections of help.
+///
+/// # Example
+/// ```rust
+/// use cliutil::constexpr::HelpLabels;
+///
+/// static LABELS: HelpLabels = HelpLabels {
+///     flags: "Options:",
+///     ..HelpLabels::new()
+/// };
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HelpLabels<'a> {
+    pub usage: &'a str,
+    pub flags: &'a str,
+    pub parameters: &'a str,
+    pub arguments: &'a str,
+    pub subcommands: &'a str,
+}
+
+impl HelpLabels<'_> {
+    /// The default labels (`Usage:`, `Flags:`, `Parameters:`, `Arguments:` and `Subcommands:`).
+    pub const fn new() -> Self {
+        Self {
+            usage: "Usage:",
+            flags: "Flags:",
+            parameters: "Parameters:",
+            arguments: "Arguments:",
+            subcommands: "Subcommands:",
+        }
+    }
+}
+
+impl Default for HelpLabels<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// How help is rendered by [`format_help`](crate::constexpr::Application::format_help).
+///
+/// # Example
+/// ```rust
+/// use cliutil::constexpr::HelpOptions;
+///
+/// let options = HelpOptions {
+///     width: 40,
+///     ..HelpOptions::new()
+/// };
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HelpOptions {
+    /// The console width descriptions are wrapped and truncated to.
+    pub width: usize,
+    /// Whether the section headers are highlighted with terminal escape codes.
+    pub color: bool,
+    pub style: HelpStyle,
+}
+
+impl HelpOptions {
+    /// The options used when help is printed (80 columns, no color, compact style).
+    pub const fn new() -> Self {
+        Self {
+            width: CONSOLE_WIOTH,
+            color: false,
+            style: HelpStyle::Compact,
+        }
+    }
+
+    /// The width of the name column of compact help, where descriptions start.
+    pub const fn name_width(&self) -> usize {
+        NAME_WIDTH
+    }
+
+    /// The width of the description column of compact help, descriptions are truncated to it.
+    pub const fn description_width(&self) -> usize {
+        self.width.saturating_sub(NAME_WIDTH)
+    }
+}
+
+impl Default for HelpOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub(super) static DEFAULT_LABELS: HelpLabels = HelpLabels::new();
+
+/// The options help is printed with under `settings`.
+pub(super) const fn options(settings: &Settings) -> HelpOptions {
+    HelpOptions {
+        width: settings.help_width,
+        style: settings.help_style,
+        ..HelpOptions::new()
+    }
+}
+
+/// Writes the full help text of a command.
+pub(super) fn write_help<R, C: Command<R> + ?Sized>(
+    command: &C,
+    w: &mut dyn Write,
+    settings: &Settings,
+) -> io::Result<()> {
+    write_help_with(command, w, &options(settings))
+}
+
+/// Writes the full help text of a command, laid out as `options` say.
+fn write_help_with<R, C: Command<R> + ?Sized>(
+    command: &C,
+    w: &mut dyn Write,
+    options: &HelpOptions,
+) -> io::Result<()> {
+    let flags = command.flags();
+    let parameters = command.parameters();
+    let positional_names = command.positional_names();
+    let labels = command.help_labels().unwrap_or(&DEFAULT_LABELS);
+
+    writeln!(w, "{}", command.name())?;
+    write_paragraphs(command.description(), w, options.width)?;
+    writeln!(w)?;
+
+    write_usage(command, w, labels, options)?;
+    writeln!(w)?;
+
+    if !flags.is_empty() {
+        write_header(labels.flags, w, options)?;
+        for flag in flags {
+            write_argument(flag, w, options)?
+        }
+        writeln!(w)?;
+    }
+
+    if !parameters.is_empty() {
+        write_header(labels.parameters, w, options)?;
+        for param in parameters {
+            write_argument(param, w, options)?
+        }
+        writeln!(w)?;
+    }
+
+    if !positional_names.is_empty() {
+        write_header(labels.arguments, w, options)?;
+        for positional in positional_names {
+            writeln!(w, "  {}", positional_placeholder(positional))?;
+        }
+        writeln!(w)?;
+    }
+
+    write_subcommands(command, w, options, labels)?;
+
+    if let Some(after_help) = command.after_help() {
+        write!(w, "{}", after_help)?;
+        if !after_help.ends_with('\n') {
+            writeln!(w)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes the subcommands sections of a command's help, one per category.
+pub(super) fn write_subcommands<R, C: Command<R> + ?Sized>(
+    command: &C,
+    w: &mut dyn Write,
+    options: &HelpOptions,
+    labels: &HelpLabels,
+) -> io::Result<()> {
+    let subcommands = command.subcommands();
+
+    // uncategorized subcommands are listed under the default header, every group in first-seen order
+    let mut categories = Vec::new();
+    for subcommand in subcommands {
+        if !categories.contains(&subcommand.category()) {
+            categories.push(subcommand.category());
+        }
+    }
+
+    for category in categories {
+        match category {
+            Some(category) => write_header(&format!("{}:", category), w, options)?,
+            None => write_header(labels.subcommands, w, options)?,
+        }
+        for subcommand in subcommands {
+            if subcommand.category() == category {
+                write_subcommand(subcommand, w, options)?
+            }
+        }
+        writeln!(w)?;
+    }
+
+    Ok(())
+}
+
+/// Writes only the subcommands sections of a command's help, in the style of the settings.
+pub(super) fn write_subcommand_list<R, C: Command<R> + ?Sized>(
+    command: &C,
+    w: &mut dyn Write,
+    settings: &Settings,
+) -> io::Result<()> {
+    let labels = command.help_labels().unwrap_or(&DEFAULT_LABELS);
+    write_subcommands(command, w, &options(settings), labels)
+}
+
+/// Writes the full help text of a command into a string.
+pub(super) fn render_help<R, C: Command<R> + ?Sized>(command: &C, settings: &Settings) -> String {
+    let mut help = Vec::new();
+    write_help(command, &mut help, settings).expect("Writing to a Vec cannot fail.");
+    String::from_utf8(help).expect("Help is always valid UTF-8.")
+}
+
+/// Writes the full help text of a command into a string, laid out as `options` say.
+pub(super) fn format_help<R, C: Command<R> + ?Sized>(command: &C, options: &HelpOptions) -> String {
+    let mut help = Vec::new();
+    write_help_with(command, &mut help, options).expect("Writing to a Vec cannot fail.");
+    String::from_utf8(help).expect("Help is always valid UTF-8.")
+}
+
+/// Writes the header of a help section on its own line, in bold if `options` enable color.
+fn write_header(header: &str, w: &mut dyn Write, options: &HelpOptions) -> io::Result<()> {
+    if options.color {
+        writeln!(w, "\x1b[1m{}\x1b[0m", header)
+    } else {
+        writeln!(w, "{}", header)
+    }
+}
+
+/// Writes the usage synopsis of a command (e.g. `Usage: myapp [FLAGS] [--param <VALUE>] [SUBCOMMAND]`).
+fn write_usage<R, C: Command<R> + ?Sized>(
+    command: &C,
+    w: &mut dyn Write,
+    labels: &HelpLabels,
+    options: &HelpOptions,
+) -> io::Result<()> {
+    if options.color {
+        write!(w, "\x1b[1m{}\x1b[0m", labels.usage)?;
+    } else {
+        write!(w, "{}", labels.usage)?;
+    }
+    if !command.name().is_empty() {
+        write!(w, " {}", command.name())?;
+    }
+
+    if !command.flags().is_empty() {
+        write!(w, " [FLAGS]")?;
+    }
+
+    for param in command.parameters() {
+        if !param.is_cli_visible() {
+            continue;
+        }
+        write!(w, " [")?;
+        if param.long_name().is_empty() {
+            write!(w, "-{}", param.short_name())?;
+        } else {
+            write!(w, "--{}", param.long_name())?;
+        }
+        write!(w, " <{}>]", param.value_name())?;
+    }
+
+    if !command.positional_names().is_empty() {
+        for positional in command.positional_names() {
+            write!(w, " {}", positional_placeholder(positional))?;
+        }
+    } else if command.positionals().is_some() {
+        write!(w, " [ARGS]...")?;
+    }
+
+    if !command.subcommands().is_empty() {
+        if command.has_command() {
+            write!(w, " [SUBCOMMAND]")?;
+        } else {
+            write!(w, " <SUBCOMMAND>")?;
+        }
+    }
+
+    writeln!(w)
+}
+
+/// The placeholder of a named positional argument, `<NAME>` if it is required, otherwise `[NAME]`.
+fn positional_placeholder(positional: &PositionalName) -> String {
+    if positional.is_required() {
+        format!("<{}>", positional.name())
+    } else {
+        format!("[{}]", positional.name())
+    }
+}
+
+fn write_argument<A: Argument>(a: &A, w: &mut dyn Write, options: &HelpOptions) -> io::Result<()> {
+    let short_name = a.short_name();
+    let long_name = a.long_name();
+    let description = a.description();
+    let mut line_index = 0;
+
+    write!(w, "  ")?;
+    line_index += 2;
+    if !short_name.is_empty() {
+        write!(w, "-{}", short_name)?;
+        line_index += display_width(short_name) + 1;
+        if !long_name.is_empty() {
+            write!(w, ", ")?;
+            line_index += 2;
+        }
+    }
+
+    if !long_name.is_empty() {
+        write!(w, "--{}", long_name)?;
+        line_index += display_width(long_name) + 2;
+    }
+
+    if let Some(value_name) = a.value_name() {
+        write!(w, " <{}>", value_name)?;
+        line_index += display_width(value_name) + 3;
+    }
+
+    match options.style {
+        HelpStyle::Compact => {
+            let mut notes = Vec::new();
+            if a.is_deprecated() {
+                notes.push("[deprecated]".to_string());
+            }
+            notes.extend(env_note(a).into_iter().chain(value_notes(a)));
+            let notes = notes.join(" ");
+
+            // the notes are kept whole when they fit, the description is shortened to make room for them
+            let width = options.description_width();
+            let mut text = match notes.is_empty() {
+                true => truncate(description, width),
+                false => truncate(description, width.saturating_sub(display_width(&notes) + 1)),
+            }
+            .trim_end()
+            .to_string();
+            if !text.is_empty() && !notes.is_empty() {
+                text.push(' ');
+            }
+            text.push_str(&notes);
+            write_column(truncate(&text, width).trim_end(), line_index, w)
+        }
+        HelpStyle::Detailed => {
+            writeln!(w)?;
+            write_detailed_description(a.long_help().unwrap_or(description), w, options.width)?;
+            write_details(a, w)?;
+            writeln!(w)
+        }
+    }
+}
+
+/// Writes the extra details of an argument shown by the detailed style, each on its own line.
+fn write_details<A: Argument>(a: &A, w: &mut dyn Write) -> io::Result<()> {
+    let mut details = Vec::new();
+
+    if a.is_deprecated() {
+        details.push(match a.deprecation_message() {
+            Some(message) => format!("[deprecated: {}]", message),
+            None => "[deprecated]".to_string(),
+        });
+    }
+
+    if let Some(note) = env_note(a) {
+        details.push(note);
+    }
+
+    details.extend(value_notes(a));
+
+    if !a.aliases().is_empty() {
+        let aliases: Vec<String> = a
+            .aliases()
+            .iter()
+            .map(|alias| format!("--{}", alias))
+            .collect();
+        details.push(format!("[aliases: {}]", aliases.join(", ")));
+    }
+
+    for detail in &details {
+        writeln!(w, "{}{}", " ".repeat(DETAILED_INDENT), detail)?;
+    }
+
+    Ok(())
+}
+
+/// The notes listing the values an argument is restricted to (e.g. `[possible values: auto, never]`) and the value
+/// it takes when given without one (e.g. `[implicit value: always]`) or not at all (e.g. `[default: auto]`), if it has
+/// them.
+fn value_notes<A: Argument>(a: &A) -> Vec<String> {
+    let mut notes = Vec::new();
+    if !a.allowed_values().is_empty() {
+        notes.push(format!(
+            "[possible values: {}]",
+            a.allowed_values().join(", ")
+        ));
+    }
+    if let Some(value) = a.implicit_value() {
+        notes.push(format!("[implicit value: {}]", value));
+    }
+    if let Some(value) = a.default_value() {
+        notes.push(format!("[default: {}]", value));
+    }
+    notes
+}
+
+/// The note naming the environment variable of an argument (e.g. `(env: APP_TOKEN)`), if it has one.
+fn env_note<A: Argument>(a: &A) -> Option<String> {
+    let var = a.env()?;
+    if a.is_cli_visible() {
+        Some(format!("(env: {})", var))
+    } else {
+        Some(format!("(env: {} only)", var))
+    }
+}
+
+fn write_subcommand<R>(
+    subcommand: &SubCommand<R>,
+    w: &mut dyn Write,
+    options: &HelpOptions,
+) -> io::Result<()> {
+    let mut names = String::new();
+    if !subcommand.short_name().is_empty() {
+        names.push_str(subcommand.short_name());
+        names.push_str(", ");
+    }
+    names.push_str(subcommand.long_name());
+    for alias in subcommand.aliases() {
+        names.push_str(", ");
+        names.push_str(alias);
+    }
+    write!(w, "  {}", names)?;
+
+    match options.style {
+        HelpStyle::Compact => {
+            let text = truncate(subcommand.description(), options.description_width()).trim_end();
+            write_column(text, display_width(&names) + 2, w)
+        }
+        HelpStyle::Detailed => {
+            writeln!(w)?;
+            write_detailed_description(subcommand.description(), w, options.width)?;
+            writeln!(w)
+        }
+    }
+}
+
+/// Writes a free-form description wrapped to `width`, keeping its line breaks and blank lines.
+fn write_paragraphs(description: &str, w: &mut dyn Write, width: usize) -> io::Result<()> {
+    for paragraph in description.split('\n') {
+        let lines = wrap(paragraph, width);
+        if lines.is_empty() {
+            writeln!(w)?;
+        }
+        for line in lines {
+            writeln!(w, "{}", line)?;
+        }
+    }
+    Ok(())
+}
+
+/// Finishes a compact help line whose name column is `line_index` wide with `text` in the description column.
+///
+/// Nothing is padded when there is no text, so the line never ends in whitespace.
+fn write_column(text: &str, line_index: usize, w: &mut dyn Write) -> io::Result<()> {
+    if !text.is_empty() {
+        write!(
+            w,
+            "{}{}",
+            " ".repeat(NAME_WIDTH.saturating_sub(line_index).max(1)),
+            text
+        )?;
+    }
+    writeln!(w)
+}
+
+/// Writes a description indented below its name, wrapped to a console `width` wide.
+fn write_detailed_description(
+    description: &str,
+    w: &mut dyn Write,
+    width: usize,
+) -> io::Result<()> {
+    for line in wrap(description, width.saturating_sub(DETAILED_INDENT)) {
+        writeln!(w, "{}{}", " ".repeat(DETAILED_INDENT), line)?;
+    }
+    Ok(())
+}
+
+/// Splits `text` into lines of at most `width` terminal columns, breaking on whitespace.
+///
+/// Words wider than `width` are kept whole on their own line.
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+
+    for word in text.split_whitespace() {
+        if !line.is_empty() && display_width(&line) + 1 + display_width(word) > width {
+            lines.push(core::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+
+    if !line.is_empty() {
+        lines.push(line);
+    }
+
+    lines
+}
+
+/// Characters that take up two columns in a terminal (East Asian wide and fullwidth ranges).
+const WIDE_RANGES: &[(char, char)] = &[
+    ('\u{1100}', '\u{115F}'),
+    ('\u{2E80}', '\u{303E}'),
+    ('\u{3041}', '\u{33FF}'),
+    ('\u{3400}', '\u{4DBF}'),
+    ('\u{4E00}', '\u{9FFF}'),
+    ('\u{A000}', '\u{A4CF}'),
+    ('\u{AC00}', '\u{D7A3}'),
+    ('\u{F900}', '\u{FAFF}'),
+    ('\u{FE30}', '\u{FE4F}'),
+    ('\u{FF00}', '\u{FF60}'),
+    ('\u{FFE0}', '\u{FFE6}'),
+    ('\u{1F300}', '\u{1F64F}'),
+    ('\u{1F900}', '\u{1F9FF}'),
+    ('\u{20000}', '\u{2FFFD}'),
+    ('\u{30000}', '\u{3FFFD}'),
+];
+
+/// The number of terminal columns `ch` takes up.
+fn char_width(ch: char) -> usize {
+    if WIDE_RANGES
+        .iter()
+        .any(|&(start, end)| (start..=end).contains(&ch))
+    {
+        2
+    } else {
+        1
+    }
+}
+
+/// The number of terminal columns `text` takes up.
+pub(super) fn display_width(text: &str) -> usize {
+    text.chars().map(char_width).sum()
+}
+
+/// The longest prefix of `text` that fits in `width` columns.
+pub(super) fn truncate(text: &str, width: usize) -> &str {
+    let mut used = 0;
+    for (index, ch) in text.char_indices() {
+        used += char_width(ch);
+        if used > width {
+            return &text[..index];
+        }
+    }
+    text
+}
@@ -0,0 +1,190 @@
+/*
+ * Copyright 2023 Jacob R. Green
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::*;
+
+use core::ops::Deref;
+
+/// Finds the flag an argument names, as written on the command line (e.g. `--verbose` or `-v`), matching long names
+/// and aliases exactly the way parsing does.
+///
+/// Useful for custom parsing on top of a flag list. Flags that cannot be given on the command line never match.
+pub fn lookup_flag<'f>(flags: &'f [Flag<'f>], name: &str) -> Option<&'f Flag<'f>> {
+    lookup(flags, name)
+}
+
+/// Finds the parameter an argument names, as written on the command line (e.g. `--output` or `-o`), matching long
+/// names and aliases exactly the way parsing does.
+///
+/// Useful for custom parsing on top of a parameter list. Parameters that cannot be given on the command line (e.g.
+/// environment only ones) never match.
+pub fn lookup_parameter<'p>(params: &'p [Parameter<'p>], name: &str) -> Option<&'p Parameter<'p>> {
+    lookup(params, name)
+}
+
+fn lookup<'a, A: Argument>(arguments: &'a [A], name: &str) -> Option<&'a A> {
+    if let Some(long_name) = name.strip_prefix("--") {
+        arguments.find_by_long_name(long_name)
+    } else if let Some(short_name) = name.strip_prefix('-') {
+        arguments.find_by_short_name(short_name)
+    } else {
+        None
+    }
+}
+
+/// The positions of the arguments in a single flag or parameter list, by name.
+pub(super) struct NameIndex {
+    long: Map<String, usize>,
+    short: Map<String, usize>,
+}
+
+impl NameIndex {
+    #[cfg(feature = "std")]
+    fn new<A: Argument>(arguments: &[A]) -> Self {
+        let mut index = Self {
+            long: Map::new(),
+            short: Map::new(),
+        };
+        // `or_insert` keeps the first registered argument, matching the linear lookup.
+        for (position, a) in arguments.iter().enumerate() {
+            if !a.is_cli_visible() {
+                continue;
+            }
+            if !a.long_name().is_empty() {
+                index
+                    .long
+                    .entry(a.long_name().to_string())
+                    .or_insert(position);
+            }
+            for alias in a.aliases() {
+                index.long.entry(alias.to_string()).or_insert(position);
+            }
+            if !a.short_name().is_empty() {
+                index
+                    .short
+                    .entry(a.short_name().to_string())
+                    .or_insert(position);
+            }
+        }
+        index
+    }
+}
+
+/// Name indexes for every flag and parameter list in a command tree, keyed by the address of the list.
+pub(super) struct LookupIndex {
+    flags: Map<usize, NameIndex>,
+    params: Map<usize, NameIndex>,
+}
+
+impl LookupIndex {
+    /// Indexes the arguments of a command and of its subcommands, up to `max_depth` levels down.
+    ///
+    /// Deeper subcommands are never reached by a parse, and stopping there keeps a cyclic tree from recursing forever.
+    /// Every subcommand list is walked once, so a tree referring back to itself is indexed in linear time.
+    #[cfg(feature = "std")]
+    pub(super) fn new<R>(
+        flags: &[Flag],
+        params: &[Parameter],
+        subcommands: &[SubCommand<R>],
+        max_depth: usize,
+    ) -> Self {
+        let mut index = Self {
+            flags: Map::new(),
+            params: Map::new(),
+        };
+        index.add(flags, params);
+
+        // breadth first, so a list is first reached at its shallowest, with the most levels left below it
+        let mut walked = std::collections::HashSet::new();
+        let mut pending = std::collections::VecDeque::from([(subcommands, max_depth)]);
+        while let Some((subcommands, remaining_depth)) = pending.pop_front() {
+            if remaining_depth == 0 || !walked.insert(subcommands.as_ptr() as usize) {
+                continue;
+            }
+            for subcommand in subcommands {
+                index.add(subcommand.flags(), subcommand.parameters());
+                pending.push_back((subcommand.subcommands(), remaining_depth - 1));
+            }
+        }
+        index
+    }
+
+    /// Indexes a flag and a parameter list, unless they already are.
+    #[cfg(feature = "std")]
+    fn add(&mut self, flags: &[Flag], params: &[Parameter]) {
+        if !flags.is_empty() {
+            self.flags
+                .entry(flags.as_ptr() as usize)
+                .or_insert_with(|| NameIndex::new(flags));
+        }
+        if !params.is_empty() {
+            self.params
+                .entry(params.as_ptr() as usize)
+                .or_insert_with(|| NameIndex::new(params));
+        }
+    }
+
+    pub(super) fn flags<'s>(&self, flags: &'s [Flag<'s>]) -> Lookup<'s, '_, Flag<'s>> {
+        Lookup {
+            items: flags,
+            index: self.flags.get(&(flags.as_ptr() as usize)),
+        }
+    }
+
+    pub(super) fn params<'s>(&self, params: &'s [Parameter<'s>]) -> Lookup<'s, '_, Parameter<'s>> {
+        Lookup {
+            items: params,
+            index: self.params.get(&(params.as_ptr() as usize)),
+        }
+    }
+}
+
+/// A list of flags or parameters, resolved through its index when there is one.
+pub(super) struct Lookup<'s, 'i, T> {
+    items: &'s [T],
+    index: Option<&'i NameIndex>,
+}
+
+impl<'s, T> Lookup<'s, '_, T> {
+    /// A lookup that always scans the list linearly.
+    pub(super) fn linear(items: &'s [T]) -> Self {
+        Self { items, index: None }
+    }
+}
+
+impl<'s, T: Argument> Lookup<'s, '_, T> {
+    pub(super) fn find_by_long_name(&self, name: &str) -> Option<&'s T> {
+        match self.index {
+            Some(index) => index.long.get(name).map(|&position| &self.items[position]),
+            None => self.items.find_by_long_name(name),
+        }
+    }
+
+    pub(super) fn find_by_short_name(&self, name: &str) -> Option<&'s T> {
+        match self.index {
+            Some(index) => index.short.get(name).map(|&position| &self.items[position]),
+            None => self.items.find_by_short_name(name),
+        }
+    }
+}
+
+impl<T> Deref for Lookup<'_, '_, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        self.items
+    }
+}
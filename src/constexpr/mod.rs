@@ -14,16 +14,36 @@
  * limitations under the License.
  */
 
-#[cfg(test)]
+// the tests drive applications through the process-level entry points
+#[cfg(all(test, feature = "std"))]
 mod tests;
 
+#[cfg(test)]
+mod core_tests;
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+pub mod io;
+
 mod flags;
 
-use std::fmt::Display;
-pub use flags::{Flag, FlagValue};
+use core::fmt::Display;
+pub use flags::{Flag, FlagValue, ModeValue};
 
 mod parameters;
-pub use parameters::{Parameter, ParameterValue};
+pub use parameters::{
+    MapParameterValue, MultiParameterValue, NumericParameterValue, Parameter, ParameterValue,
+    ValueHint,
+};
+
+mod positionals;
+use positionals::PositionalNames;
+pub use positionals::{PositionalName, PositionalValue, MAX_POSITIONAL_NAMES};
 
 mod subcommand;
 pub use subcommand::SubCommand;
@@ -31,6 +51,45 @@ pub use subcommand::SubCommand;
 mod application;
 pub use application::Application;
 
+mod completion;
+pub use completion::Shell;
+
+mod help;
+pub use help::{HelpLabels, HelpOptions, HelpStyle};
+
+mod lookup;
+pub use lookup::{lookup_flag, lookup_parameter};
+use lookup::{Lookup, LookupIndex};
+
+#[cfg(feature = "std")]
+mod pager;
+
+#[cfg(feature = "std")]
+mod terminal;
+
+#[cfg(feature = "std")]
+mod tokenize;
+
+mod report;
+pub use report::ColorChoice;
+
+mod slots;
+pub use slots::ArgSlot;
+
+mod sources;
+#[cfg(feature = "std")]
+pub use sources::EnvSource;
+pub use sources::{DefaultSource, ValueSource};
+
+mod suggest;
+
+mod trace;
+#[cfg(feature = "std")]
+use trace::JsonTraceWriter;
+
+mod values;
+pub use values::{ArgValue, POSITIONALS_KEY};
+
 pub mod builders {
     pub use super::flags::FlagBuilder;
     pub use super::parameters::ParameterBuilder;
@@ -40,21 +99,95 @@ pub mod builders {
 
 type Callback<'a, R> = &'a (dyn Fn() -> R + Sync);
 
-const CONSOLE_WIOTH: usize = 80;
-const NAME_WIDTH: usize = 20;
-const DESCRIPTION_WIDTH: usize = CONSOLE_WIOTH - NAME_WIDTH;
+/// The map arguments are collected and indexed by name in, hashed with `std` and ordered without it.
+#[cfg(feature = "std")]
+type Map<K, V> = std::collections::HashMap<K, V>;
+#[cfg(not(feature = "std"))]
+type Map<K, V> = alloc::collections::BTreeMap<K, V>;
+
+/// Opens the writer command line errors are printed to.
+#[cfg(feature = "std")]
+type ErrorWriter = fn() -> Box<dyn io::Write>;
+
+/// Describes a command line error in the user's language, in place of its English message.
+#[cfg(feature = "std")]
+type ErrorFormatter = fn(&CommandLineError) -> String;
+
+/// Runs a command that matches no subcommand, given its name and the arguments after it.
+type ExternalSubcommand<R> = fn(&str, &[String]) -> R;
+
+#[cfg(feature = "async")]
+type AsyncCallback<'a, R> =
+    &'a (dyn Fn() -> core::pin::Pin<Box<dyn core::future::Future<Output = R>>> + Sync);
 
 #[derive(Debug)]
 pub enum CommandLineError {
     UnknownArgument(String),
     UnexpectedParameter(String),
     UnknownCommand(String),
-    ExpectedValue(String),
+    ExpectedValue {
+        arg: String,
+        names: String,
+        value_name: String,
+    },
     ExpectedSubcommand,
+    ValidationFailed {
+        name: String,
+        message: String,
+    },
+    MissingDependency {
+        flag: String,
+        requires: String,
+    },
+    ParseError(String),
+    FlagTakesNoValue(String),
+    MaxDepthExceeded,
+    MissingRequiredParameter(String),
+    MissingOneOf(Vec<String>),
+    ConflictingFlags(Vec<String>),
+    InvalidValue {
+        name: String,
+        value: String,
+        expected: String,
+    },
+    /// A long parameter was given without `=value` while [`with_require_equals`](builders::ApplicationBuilder::with_require_equals)
+    /// is enabled, `suggestion` is the form it should have been given in (e.g. `--output=file`).
+    MissingEquals {
+        arg: String,
+        suggestion: String,
+    },
+}
+
+impl CommandLineError {
+    /// A stable identifier of the kind of error (e.g. `"unknown_argument"`), independent of its message.
+    pub const fn kind(&self) -> &'static str {
+        match self {
+            CommandLineError::UnknownArgument(_) => "unknown_argument",
+            CommandLineError::UnexpectedParameter(_) => "unexpected_parameter",
+            CommandLineError::UnknownCommand(_) => "unknown_command",
+            CommandLineError::ExpectedValue { .. } => "expected_value",
+            CommandLineError::ExpectedSubcommand => "expected_subcommand",
+            CommandLineError::ValidationFailed { .. } => "validation_failed",
+            CommandLineError::MissingDependency { .. } => "missing_dependency",
+            CommandLineError::ParseError(_) => "parse_error",
+            CommandLineError::FlagTakesNoValue(_) => "flag_takes_no_value",
+            CommandLineError::MaxDepthExceeded => "max_depth_exceeded",
+            CommandLineError::MissingRequiredParameter(_) => "missing_required_parameter",
+            CommandLineError::MissingOneOf(_) => "missing_one_of",
+            CommandLineError::ConflictingFlags(_) => "conflicting_flags",
+            CommandLineError::InvalidValue { .. } => "invalid_value",
+            CommandLineError::MissingEquals { .. } => "missing_equals",
+        }
+    }
+
+    /// The exit code of a process failing with the error, `2` as is conventional for command line usage errors.
+    pub const fn exit_code(&self) -> i32 {
+        2
+    }
 }
 
 impl Display for CommandLineError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             CommandLineError::UnknownArgument(arg) => {
                 write!(f, "Unknown argument: {}", arg)
@@ -65,164 +198,777 @@ impl Display for CommandLineError {
             CommandLineError::UnknownCommand(cmd) => {
                 write!(f, "Unknown command: {}", cmd)
             }
-            CommandLineError::ExpectedValue(arg) => {
-                write!(f, "Expected value for parameter: {}", arg)
+            CommandLineError::ExpectedValue {
+                names, value_name, ..
+            } => {
+                write!(f, "Expected <{}> after {}", value_name, names)
             }
             CommandLineError::ExpectedSubcommand => {
                 write!(f, "Expected subcommand")
             }
+            CommandLineError::ValidationFailed { name, message } => {
+                write!(f, "Invalid value for parameter {}: {}", name, message)
+            }
+            CommandLineError::MissingDependency { flag, requires } => {
+                write!(f, "{} requires {}", flag, requires)
+            }
+            CommandLineError::ParseError(message) => {
+                write!(f, "Parse error: {}", message)
+            }
+            CommandLineError::FlagTakesNoValue(arg) => {
+                write!(f, "Flag takes no value: {}", arg)
+            }
+            CommandLineError::MaxDepthExceeded => {
+                write!(f, "Maximum subcommand depth exceeded")
+            }
+            CommandLineError::MissingRequiredParameter(param) => {
+                write!(f, "Missing required parameter: {}", param)
+            }
+            CommandLineError::MissingOneOf(names) => {
+                write!(f, "Expected one of {}", names.join(", "))
+            }
+            CommandLineError::ConflictingFlags(names) => {
+                write!(f, "Only one of {} may be given", names.join(", "))
+            }
+            CommandLineError::InvalidValue {
+                name,
+                value,
+                expected,
+            } => write!(
+                f,
+                "Invalid value for {}: '{}' is not {}",
+                name, value, expected
+            ),
+            CommandLineError::MissingEquals { arg, suggestion } => {
+                write!(
+                    f,
+                    "Expected a value after '=' for {}, e.g. {}",
+                    arg, suggestion
+                )
+            }
         }
     }
 }
 
+// No variant wraps another error, so there is never a `source`.
+impl core::error::Error for CommandLineError {}
+
+/// A misconfiguration reported by the `try_build` of a builder, where `build` would panic instead.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BuildError {
+    /// A flag or parameter has neither a short nor a long name, or a subcommand has no long name.
+    MissingName,
+    /// A flag or parameter has no value to set.
+    MissingValue,
+    /// A command has neither a default command nor any subcommands.
+    NoCommandOrSubcommand,
+    /// Two flags or parameters, or two subcommands, of the same command share a name (e.g. `--verbose`).
+    DuplicateName(String),
+}
+
+impl Display for BuildError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            BuildError::MissingName => write!(f, "Missing name"),
+            BuildError::MissingValue => write!(f, "Missing value"),
+            BuildError::NoCommandOrSubcommand => {
+                write!(f, "Expected a default command or at least one subcommand")
+            }
+            BuildError::DuplicateName(name) => write!(f, "Duplicate name: {}", name),
+        }
+    }
+}
+
+impl core::error::Error for BuildError {}
+
+/// What to do with an argument that matches no flag or parameter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UnknownDisposition {
+    /// Report it as usual (or collect it, if unknown arguments are ignored).
+    Error,
+    /// Skip it.
+    Ignore,
+    /// Treat it as a positional argument, if the command collects them.
+    Positional,
+}
+
+/// A user callback deciding what to do with each unknown argument.
+type UnknownHandler = fn(&str) -> UnknownDisposition;
+
+/// Something that happened while parsing, reported to the trace callback.
+///
+/// Arguments are identified by their long name or, if they have none, their short name.
+#[derive(Debug)]
+pub enum ParseEvent<'e> {
+    /// A flag was set.
+    FlagMarked(&'e str),
+    /// A parameter was given a value.
+    ParameterSet { name: &'e str, value: &'e str },
+    /// Parsing continued into a subcommand.
+    SubcommandDispatched(&'e str),
+    /// Parsing failed.
+    Error(&'e CommandLineError),
+}
+
+/// A user callback notified of every parse event.
+type TraceCallback = fn(&ParseEvent);
+
+/// A user callback receiving the value of every flag and parameter, by long name, once parsing has succeeded.
+type Binder = fn(&str, ArgValue);
+
+/// A user callback looking up the configured value of a parameter by its long name.
+type ConfigSource = fn(&str) -> Option<String>;
+
 /// A command line executable.
 trait Command<R> {
     fn name(&self) -> &str;
     fn description(&self) -> &str;
 
-    fn flags(&self) -> &[Flag];
-    fn parameters(&self) -> &[Parameter];
-    fn subcommands(&self) -> &[SubCommand<R>];
-    fn command(&self) -> Option<Callback<R>>;
+    fn flags(&self) -> &[Flag<'_>];
+    fn parameters(&self) -> &[Parameter<'_>];
+    fn subcommands(&self) -> &[SubCommand<'_, R>];
+    fn positionals(&self) -> Option<&PositionalValue>;
 
-    fn help_enabled(&self) -> bool;
+    /// The long names of arguments of which exactly one must be given.
+    fn required_one_of(&self) -> &[&str] {
+        &[]
+    }
 
-    fn print_help(&self) {
-        let name = self.name();
-        let description = self.description();
-        let flags = self.flags();
-        let parameters = self.parameters();
-        let subcommands = self.subcommands();
+    /// The positional arguments named in the command's usage and help.
+    fn positional_names(&self) -> &[PositionalName<'_>] {
+        &[]
+    }
+
+    fn command(&self) -> Option<Callback<'_, R>>;
+    #[cfg(feature = "async")]
+    fn async_command(&self) -> Option<AsyncCallback<'_, R>>;
+
+    /// Whether the command has a callback to dispatch to when no subcommand is selected.
+    fn has_command(&self) -> bool {
+        #[cfg(feature = "async")]
+        if self.async_command().is_some() {
+            return true;
+        }
+        self.command().is_some()
+    }
+
+    fn help_command_enabled(&self) -> bool;
+    fn help_flag_enabled(&self) -> bool;
+
+    /// Text printed verbatim at the end of the command's help.
+    fn after_help(&self) -> Option<&str>;
+
+    /// The version printed by `--version`, if the command has its own.
+    fn version(&self) -> Option<&str> {
+        None
+    }
+
+    /// The section titles used in the command's help, if not the defaults.
+    fn help_labels(&self) -> Option<&HelpLabels<'_>>;
+
+    /// Whether the command's help is printed, instead of an error, when no subcommand is selected.
+    fn list_on_missing(&self) -> bool {
+        false
+    }
+
+    /// Whether the command's help is printed, instead of an error, when it is given no arguments at all.
+    fn empty_args_help(&self) -> bool {
+        false
+    }
+
+    /// Whether the available subcommands are listed on stderr when no subcommand is selected.
+    fn require_subcommand(&self) -> bool {
+        false
+    }
 
-        println!("{}", name);
-        println!("{}", description);
-        println!();
+    /// Where commands matching no subcommand are handed, if they are not an error.
+    fn default_subcommand(&self) -> Option<ExternalSubcommand<R>> {
+        None
+    }
+
+    /// Whether a command matching no subcommand, and everything after it, are positionals of the default command.
+    fn unknown_as_positional(&self) -> bool {
+        false
+    }
+
+    fn write_help(&self, w: &mut dyn io::Write, settings: &Settings) -> io::Result<()> {
+        help::write_help(self, w, settings)
+    }
+
+    /// Prints the help to the output of `ctx`, through a pager if it is the terminal and paging is enabled.
+    fn print_help(&self, ctx: &mut Context) {
+        #[cfg(feature = "std")]
+        if ctx.pager {
+            let mut help = Vec::new();
+            let _ = self.write_help(&mut help, ctx.settings);
+            if pager::page(&String::from_utf8_lossy(&help)).is_err() {
+                let _ = ctx.out.write_all(&help);
+            }
+            return;
+        }
+        let _ = self.write_help(ctx.out, ctx.settings);
+    }
+}
+
+/// Application wide settings shared with every subcommand.
+#[derive(Clone, Copy)]
+struct Settings {
+    help_style: HelpStyle,
+    help_width: usize,
+    plus_minus_flags: bool,
+    slash_options: bool,
+    require_equals: bool,
+    flag_values: bool,
+    ignore_unknown: bool,
+    unknown_handler: Option<UnknownHandler>,
+    timing: bool,
+    indexed_lookup: bool,
+    case_insensitive: bool,
+    trace: Option<TraceCallback>,
+    config_source: Option<ConfigSource>,
+    interactive_prompts: bool,
+    /// How many levels of subcommands a parse may descend into.
+    max_depth: usize,
+    color: ColorChoice,
+    pager: bool,
+}
+
+impl Settings {
+    const fn new() -> Self {
+        Self {
+            help_style: HelpStyle::Compact,
+            help_width: help::CONSOLE_WIOTH,
+            plus_minus_flags: false,
+            slash_options: false,
+            require_equals: false,
+            flag_values: false,
+            ignore_unknown: false,
+            unknown_handler: None,
+            timing: false,
+            indexed_lookup: false,
+            case_insensitive: false,
+            trace: None,
+            config_source: None,
+            interactive_prompts: false,
+            max_depth: 64,
+            color: ColorChoice::Auto,
+            pager: false,
+        }
+    }
+}
+
+/// State shared by every level of a single parse.
+struct Context<'c> {
+    /// Where help and other informational output is written.
+    out: &'c mut dyn io::Write,
+    /// Where warnings are written.
+    err: &'c mut dyn io::Write,
+    settings: &'c Settings,
+    /// Where unknown flags and parameters are collected, if they are ignored rather than reported.
+    unknown_args: Option<&'c PositionalValue>,
+    /// The name indexes used to resolve arguments, if indexed lookup is enabled.
+    index: Option<&'c LookupIndex>,
+    /// How many subcommands the parse has descended into.
+    depth: usize,
+    /// Where prompted values are read from, instead of standard input.
+    #[cfg(feature = "std")]
+    input: Option<&'c mut dyn std::io::BufRead>,
+    /// The long name that selects a subcommand by value (e.g. `command` for `--command=deploy`), if any.
+    subcommand_flag: Option<&'c str>,
+    /// The long names of the subcommands the parse has descended into, in order.
+    path: Vec<String>,
+    /// The long name that prints the resolved configuration instead of running a command (e.g. `print-config`), if any.
+    config_dump_flag: Option<&'c str>,
+    /// Whether the configuration dump flag was given.
+    config_dump_requested: bool,
+    /// The resolved configuration of the commands the parse has descended through, if it can be dumped.
+    config_lines: Vec<String>,
+    /// Whether help is shown in a pager, only ever set when the output is the standard output of a terminal.
+    #[cfg(feature = "std")]
+    pager: bool,
+    /// The version of the innermost command the parse has descended into that has one, if any.
+    version: Option<String>,
+    /// Where every parse event is written as a line of JSON, if anywhere.
+    #[cfg(feature = "std")]
+    json_trace: Option<&'c JsonTraceWriter>,
+    /// The layers of configured parameter values, in precedence order.
+    value_sources: &'c [&'c (dyn ValueSource + Sync)],
+}
+
+impl<'c> Context<'c> {
+    fn new(
+        out: &'c mut dyn io::Write,
+        err: &'c mut dyn io::Write,
+        settings: &'c Settings,
+        unknown_args: Option<&'c PositionalValue>,
+        index: Option<&'c LookupIndex>,
+        subcommand_flag: Option<&'c str>,
+        config_dump_flag: Option<&'c str>,
+    ) -> Self {
+        Self {
+            out,
+            err,
+            settings,
+            unknown_args,
+            index,
+            depth: 0,
+            #[cfg(feature = "std")]
+            input: None,
+            subcommand_flag,
+            path: Vec::new(),
+            config_dump_flag,
+            config_dump_requested: false,
+            config_lines: Vec::new(),
+            #[cfg(feature = "std")]
+            pager: false,
+            version: None,
+            #[cfg(feature = "std")]
+            json_trace: None,
+            value_sources: &[],
+        }
+    }
+
+    /// Resolves the flags of a command, through the index if there is one.
+    fn flags<'s>(&self, flags: &'s [Flag<'s>]) -> Lookup<'s, 'c, Flag<'s>> {
+        match self.index {
+            Some(index) => index.flags(flags),
+            None => Lookup::linear(flags),
+        }
+    }
+
+    /// Resolves the parameters of a command, through the index if there is one.
+    fn params<'s>(&self, params: &'s [Parameter<'s>]) -> Lookup<'s, 'c, Parameter<'s>> {
+        match self.index {
+            Some(index) => index.params(params),
+            None => Lookup::linear(params),
+        }
+    }
+
+    /// Handles an argument that matches no flag or parameter, consulting the unknown handler if there is one.
+    ///
+    /// Unless the handler says otherwise, the argument is collected if unknown arguments are ignored,
+    /// or `error` is returned.
+    fn unknown_argument(
+        &mut self,
+        arg: &str,
+        positionals: Option<&PositionalValue>,
+        error: CommandLineError,
+    ) -> Result<(), CommandLineError> {
+        let disposition = match self.settings.unknown_handler {
+            Some(handler) => handler(arg),
+            None => UnknownDisposition::Error,
+        };
+        let collector = match disposition {
+            UnknownDisposition::Ignore => return Ok(()),
+            UnknownDisposition::Positional => positionals,
+            UnknownDisposition::Error => self.unknown_args,
+        };
+        match collector {
+            Some(collector) => {
+                collector.push(arg.to_string());
+                Ok(())
+            }
+            None => Err(error),
+        }
+    }
+
+    /// Marks a flag that is present on the command line, or flips it if it is a toggle.
+    fn mark(&mut self, flag: &Flag) {
+        self.set_flag(flag, !(flag.is_toggle() && flag.is_present()))
+    }
+
+    /// Sets a flag that is present on the command line, enabled (`+flag`) or disabled (`-flag`).
+    fn set_flag(&mut self, flag: &Flag, value: bool) {
+        self.warn_deprecated(flag);
+        flag.set(value);
+        if value {
+            self.trace(&ParseEvent::FlagMarked(flag.key_name()));
+            flag.notify_set();
+        }
+    }
+
+    /// Marks a parameter with an optional value that is present on the command line without one.
+    fn mark_given(&mut self, param: &Parameter) {
+        self.warn_deprecated(param);
+        param.mark_given();
+    }
+
+    /// Sets the value of a parameter that is present on the command line.
+    fn set_value(&mut self, param: &Parameter, value: &str) -> Result<(), CommandLineError> {
+        self.warn_deprecated(param);
+        param.set_value(format_parameter_value(value))?;
+        self.trace(&ParseEvent::ParameterSet {
+            name: param.key_name(),
+            value,
+        });
+        Ok(())
+    }
 
-        if !flags.is_empty() {
-            println!("Flags:");
-            for flag in flags {
-                print_help_for_argument(flag)
+    /// Sets every parameter that is not on the command line to, in order of precedence, the value of its environment
+    /// variable, of the first value source that has one, of the config source or its default value.
+    ///
+    /// These values leave the parameters not present, so requirements and one-of groups only count the command line.
+    fn apply_config(&mut self, params: &[Parameter]) -> Result<(), CommandLineError> {
+        for param in params {
+            if param.has_value() {
+                continue;
+            }
+            #[cfg(feature = "std")]
+            if let Some(value) = param.env().and_then(|var| std::env::var(var).ok()) {
+                param.set_fallback(value)?;
+                continue;
+            }
+            // sources look parameters up by long name
+            let name = param.long_name();
+            let configured = match name.is_empty() {
+                true => None,
+                false => self
+                    .value_sources
+                    .iter()
+                    .find_map(|source| source.value(name))
+                    .or_else(|| self.settings.config_source.and_then(|source| source(name))),
+            };
+            let value = configured.or_else(|| param.default_value().map(str::to_string));
+            if let Some(value) = value {
+                param.set_fallback(value)?;
             }
-            println!();
         }
+        Ok(())
+    }
 
-        if !parameters.is_empty() {
-            println!("Parameters:");
-            for param in parameters {
-                print_help_for_argument(param)
+    /// Ensures every required parameter has a value, prompting for missing ones if interactive prompts are enabled.
+    fn check_required<'p>(
+        &mut self,
+        params: impl IntoIterator<Item = &'p Parameter<'p>>,
+    ) -> Result<(), CommandLineError> {
+        for param in params {
+            if !param.is_required() || param.has_value() {
+                continue;
+            }
+            match self.prompt(param) {
+                Some(value) => self.set_value(param, &value)?,
+                None => {
+                    return Err(CommandLineError::MissingRequiredParameter(
+                        param.display_name(),
+                    ))
+                }
             }
-            println!();
         }
+        Ok(())
+    }
 
-        if !subcommands.is_empty() {
-            println!("Subcommands:");
-            for subcommand in subcommands {
-                print_help_for_subcommand(subcommand)
+    /// Asks the user for the value of `param`, if interactive prompts are enabled and there is someone to ask.
+    #[cfg(feature = "std")]
+    fn prompt(&mut self, param: &Parameter) -> Option<String> {
+        if !self.settings.interactive_prompts {
+            return None;
+        }
+
+        let mut stdin;
+        let input: &mut dyn std::io::BufRead = match self.input.as_deref_mut() {
+            Some(input) => input,
+            None if terminal::is_terminal(terminal::Stream::Stdin) => {
+                stdin = std::io::stdin().lock();
+                &mut stdin
             }
-            println!();
+            None => return None,
+        };
+
+        let _ = write!(self.err, "Enter value for {}: ", param.display_name());
+        let _ = self.err.flush();
+
+        let mut line = String::new();
+        match input.read_line(&mut line) {
+            Ok(0) | Err(_) => None,
+            Ok(_) => Some(line.trim_end_matches(['\r', '\n']).to_string()),
         }
     }
-}
 
-fn print_help_for_argument<A: Argument>(a: &A) {
-    let short_name = a.short_name();
-    let long_name = a.long_name();
-    let description = a.description();
-    let mut line_index = 0;
+    /// Without `std` there is no terminal to prompt on.
+    #[cfg(not(feature = "std"))]
+    fn prompt(&mut self, _param: &Parameter) -> Option<String> {
+        None
+    }
 
-    print!("  ");
-    line_index += 2;
-    if !short_name.is_empty() {
-        print!("-{}", short_name);
-        line_index += short_name.len() + 1;
-        if !long_name.is_empty() {
-            print!(", ");
-            line_index += 2;
+    /// Records the resolved configuration of a command that is finished with, if the configuration can be dumped.
+    fn record_config(&mut self, flags: &[Flag], params: &[Parameter]) {
+        if self.config_dump_flag.is_some() {
+            self.config_lines
+                .extend(values::config_lines(flags, params))
         }
     }
 
-    if !long_name.is_empty() {
-        print!("--{}", long_name);
-        line_index += long_name.len() + 2;
+    /// Prints the resolved configuration of every command the parse went through, one argument per line.
+    fn dump_config(&mut self, flags: &[Flag], params: &[Parameter]) {
+        self.record_config(flags, params);
+        for line in &self.config_lines {
+            let _ = writeln!(self.out, "{}", line);
+        }
     }
 
-    print!("{}", " ".repeat(NAME_WIDTH.saturating_sub(line_index)));
+    /// Reports an event to the trace callback and the JSON trace writer, if there are any.
+    fn trace(&self, event: &ParseEvent) {
+        if let Some(trace) = self.settings.trace {
+            trace(event)
+        }
+        #[cfg(feature = "std")]
+        if let Some(Ok(mut w)) = self.json_trace.map(|w| w.lock()) {
+            let _ = trace::write_json(&mut *w, event);
+        }
+    }
 
-    let desc_len = DESCRIPTION_WIDTH.min(description.len());
+    /// Points out the flag or parameter that an unknown long name (e.g. `--verbse`) was most likely meant to be.
+    fn suggest_long_name(&mut self, name: &str, flags: &[Flag], params: &[Parameter]) {
+        let candidates = flags.iter().map(|flag| flag.long_name()).chain(
+            params
+                .iter()
+                .filter(|param| param.is_cli_visible())
+                .map(|param| param.long_name()),
+        );
+        if let Some(suggestion) = suggest::closest(name, candidates) {
+            let _ = writeln!(self.err, "Did you mean --{}?", suggestion);
+        }
+    }
 
-    println!("{}", &description[..desc_len]);
+    fn warn_deprecated<A: Argument>(&mut self, a: &A) {
+        if !a.is_deprecated() {
+            return;
+        }
+        let _ = match a.deprecation_message() {
+            Some(message) => writeln!(
+                self.err,
+                "warning: {} is deprecated: {}",
+                a.display_name(),
+                message
+            ),
+            None => writeln!(self.err, "warning: {} is deprecated", a.display_name()),
+        };
+    }
 }
 
-fn print_help_for_subcommand<R>(subcommand: &SubCommand<R>) {
-    print!("  {}", subcommand.long_name());
-    print!(
-        "{}",
-        " ".repeat(NAME_WIDTH.saturating_sub(subcommand.long_name().len() + 2))
-    );
+/// The result of a successful dispatch, returned by [`Application::execute_no_exit`].
+#[derive(Debug, PartialEq, Eq)]
+pub enum RunOutcome<R> {
+    /// The matched command ran and returned a value.
+    Completed(R),
+    /// Help was printed instead of running a command.
+    HelpShown,
+    /// The version was printed instead of running a command.
+    VersionShown,
+    /// The resolved configuration was printed instead of running a command.
+    ConfigPrinted,
+}
 
-    let desc_len = DESCRIPTION_WIDTH.min(subcommand.description().len());
-    println!("{}", &subcommand.description()[..desc_len]);
+/// The result of successfully parsing the command line.
+enum ParseOutcome<'c, R> {
+    /// Parsing finished on a command whose callback should now be dispatched.
+    Dispatch(&'c dyn Command<R>),
+    /// Parsing stopped at a command matching no subcommand, to be handed to the default subcommand with the arguments
+    /// after it.
+    External(ExternalSubcommand<R>, String, Vec<String>),
+    /// Help was printed instead of selecting a command.
+    HelpShown,
+    /// The version was printed instead of selecting a command.
+    VersionShown,
+    /// The resolved configuration was printed instead of selecting a command.
+    ConfigPrinted,
 }
 
 trait Executable<R> {
-    fn execute<T: AsRef<str>, It: Iterator<Item = T>>(
+    fn parse<T: AsRef<str>, It: Iterator<Item = T>>(
         &self,
         args: It,
-    ) -> Result<R, CommandLineError>;
+        ctx: &mut Context,
+        inherited: &[&Parameter],
+    ) -> Result<ParseOutcome<'_, R>, CommandLineError>;
 }
 
 impl<R, Ty: Command<R>> Executable<R> for Ty {
-    fn execute<T: AsRef<str>, It: Iterator<Item = T>>(
+    fn parse<T: AsRef<str>, It: Iterator<Item = T>>(
         &self,
         mut args: It,
-    ) -> Result<R, CommandLineError> {
-        let flags = self.flags();
-        let params = self.parameters();
+        ctx: &mut Context,
+        inherited: &[&Parameter],
+    ) -> Result<ParseOutcome<'_, R>, CommandLineError> {
+        #[cfg(debug_assertions)]
+        flags::assert_distinct_values(self.flags());
+
+        let flags = ctx.flags(self.flags());
+        let params = ctx.params(self.parameters());
         let subcommands = self.subcommands();
-        let command = self.command();
+        let positionals = self.positionals();
+        let mut no_args = true;
+
+        // an argument that was looked at, but not consumed, by the previous argument
+        let mut pending = None;
+        // whether `--` was given, after which nothing is an option of this command (a subcommand still parses its own)
+        let mut options_ended = false;
 
-        while let Some(arg) = args.next() {
+        if let Some(version) = self.version() {
+            ctx.version = Some(version.to_string())
+        }
+
+        while let Some(arg) = pending.take().or_else(|| args.next()) {
             let arg = arg.as_ref();
+            no_args = false;
+
+            // slash option (/example or /example:value), read as its long form
+            let normalized = match options_ended {
+                false => slash_option(arg, &flags, &params, inherited, ctx, self),
+                true => None,
+            };
+            let arg = normalized.as_deref().unwrap_or(arg);
+
+            // end of options (--)
+            if !options_ended && arg == "--" {
+                options_ended = true;
+                continue;
+            }
+            let is_option = !options_ended;
 
+            // enabled flag (+example)
+            if is_option && ctx.settings.plus_minus_flags && arg.is_plus_name() {
+                if let Some(flag) = flags.find_by_long_name(&arg[1..]) {
+                    ctx.set_flag(flag, true)
+                } else {
+                    let error = CommandLineError::UnknownArgument(arg.to_string());
+                    ctx.unknown_argument(arg, positionals, error)?
+                }
+            }
             // long name (--example)
-            if arg.is_long_name() {
+            else if is_option && arg.is_long_name() {
                 let arg_slice = &arg[2..];
 
                 // parameter
                 if let Some((name, value)) = split_parameter(arg_slice) {
-                    if let Some(param) = params.find_by_long_name(name) {
-                        param.set_value(format_parameter_value(value))
-                    } else {
+                    // no name (--=example), which no argument has, even one without a long name
+                    if name.is_empty() {
+                        let error = CommandLineError::UnknownArgument(arg.to_string());
+                        ctx.unknown_argument(arg, positionals, error)?
+                    }
+                    // subcommand selected by flag (--command=example)
+                    else if ctx.subcommand_flag == Some(name) {
+                        let Some(command) = subcommands
+                            .iter()
+                            .find(|command| command.is_named(value, ctx.settings.case_insensitive))
+                        else {
+                            return Err(CommandLineError::UnknownCommand(value.to_string()));
+                        };
+                        return descend(self, command, &flags, &params, inherited, args, ctx);
+                    } else if let Some(param) = params
+                        .find_by_long_name(name)
+                        .or_else(|| inherited.find_by_long_name(name))
+                    {
+                        ctx.set_value(param, value)?
+                    } else if let Some(flag) = flags.find_by_long_name(name) {
+                        // explicit boolean (--example=false)
+                        set_flag_value(flag, arg, value, ctx)?
+                    } else if self.help_flag_enabled() && name == "help" {
                         return Err(CommandLineError::UnexpectedParameter(arg.to_string()));
+                    } else {
+                        let error = CommandLineError::UnknownArgument(arg.to_string());
+                        if let Err(error) = ctx.unknown_argument(arg, positionals, error) {
+                            if self.help_flag_enabled() {
+                                ctx.suggest_long_name(name, &flags, &params)
+                            }
+                            return Err(error);
+                        }
                     }
                 }
                 // flag
                 else if let Some(flag) = flags.find_by_long_name(arg_slice) {
-                    flag.mark()
+                    ctx.mark(flag)
+                }
+                // parameter with separate value (--example value)
+                else if let Some(param) = params
+                    .find_by_long_name(arg_slice)
+                    .or_else(|| inherited.find_by_long_name(arg_slice))
+                {
+                    if !param.has_optional_value() && !ctx.settings.require_equals {
+                        let value = take_value(param, arg, &mut args, &mut pending)?;
+                        ctx.set_value(param, &value)?
+                    } else if let Some(value) = param.implicit_value() {
+                        ctx.set_value(param, value)?
+                    } else if param.has_optional_value() {
+                        ctx.mark_given(param)
+                    } else {
+                        return Err(missing_equals(param, arg, args.next()));
+                    }
+                }
+                // help flag
+                else if self.help_flag_enabled() && arg_slice == "help" {
+                    self.print_help(ctx);
+                    return Ok(ParseOutcome::HelpShown);
+                }
+                // version flag
+                else if let (Some(version), "version") = (&ctx.version, arg_slice) {
+                    let _ = match self.name() {
+                        "" => writeln!(ctx.out, "{}", version),
+                        name => writeln!(ctx.out, "{} {}", name, version),
+                    };
+                    return Ok(ParseOutcome::VersionShown);
+                }
+                // configuration dump flag
+                else if ctx.config_dump_flag == Some(arg_slice) {
+                    ctx.config_dump_requested = true
                 }
                 // unknown argument
                 else {
                     // if self.help_enabled() {
                     //     eprintln!("Unknown argument: {}", arg);
                     // }
-                    return Err(CommandLineError::UnknownArgument(arg.to_string()));
+                    let error = CommandLineError::UnknownArgument(arg.to_string());
+                    if let Err(error) = ctx.unknown_argument(arg, positionals, error) {
+                        if self.help_flag_enabled() {
+                            ctx.suggest_long_name(arg_slice, &flags, &params)
+                        }
+                        return Err(error);
+                    }
                 }
             }
             // short name (-e)
-            else if arg.is_short_name() {
+            else if is_option && arg.is_short_name() {
                 let arg_slice = &arg[1..];
 
                 // flag
                 if let Some(flag) = flags.find_by_short_name(arg_slice) {
-                    flag.mark()
+                    ctx.mark(flag)
                 }
                 // parameter
-                else if let Some(param) = params.find_by_short_name(arg_slice) {
-                    if let Some(value) = args.next() {
-                        let value = value.as_ref();
-                        param.set_value(format_parameter_value(value))
-                    } else {
-                        return Err(CommandLineError::ExpectedValue(arg.to_string()));
+                else if let Some(param) = params
+                    .find_by_short_name(arg_slice)
+                    .or_else(|| inherited.find_by_short_name(arg_slice))
+                {
+                    let value = take_value(param, arg, &mut args, &mut pending)?;
+                    ctx.set_value(param, &value)?
+                }
+                // help flag
+                else if self.help_flag_enabled() && arg_slice == "h" {
+                    self.print_help(ctx);
+                    return Ok(ParseOutcome::HelpShown);
+                }
+                // flag given a value (-e=false)
+                else if let Some((flag, value)) =
+                    arg_slice.split_once('=').and_then(|(name, value)| {
+                        flags.find_by_short_name(name).map(|flag| (flag, value))
+                    })
+                {
+                    set_flag_value(flag, arg, value, ctx)?
+                }
+                // disabled flag (-example)
+                else if let (true, Some(flag)) = (
+                    ctx.settings.plus_minus_flags,
+                    flags.find_by_long_name(arg_slice),
+                ) {
+                    ctx.set_flag(flag, false)
+                }
+                // combined short names (-fg)
+                else if arg_slice.chars().count() > 1 {
+                    match parse_short_cluster(arg, &flags, &params, &mut args, &mut pending, ctx) {
+                        Err(error @ CommandLineError::UnknownArgument(_)) => {
+                            ctx.unknown_argument(arg, positionals, error)?
+                        }
+                        result => result?,
                     }
                 }
                 // unknown argument
@@ -230,38 +976,411 @@ impl<R, Ty: Command<R>> Executable<R> for Ty {
                     // if self.help_enabled() {
                     //     eprintln!("Unknown argument: {}", arg);
                     // }
-                    return Err(CommandLineError::UnknownArgument(arg.to_string()));
+                    let error = CommandLineError::UnknownArgument(arg.to_string());
+                    ctx.unknown_argument(arg, positionals, error)?
                 }
             }
+            // numeric short flag (-1), which takes precedence over the negative number
+            else if let Some(flag) = arg
+                .strip_prefix('-')
+                .filter(|_| is_option)
+                .and_then(|name| flags.find_by_short_name(name))
+            {
+                ctx.mark(flag)
+            }
             // command
             else {
-                if self.help_enabled() && arg == "help" {
-                    self.print_help();
-                    std::process::exit(0)
+                // after `--`, `help` is an argument like any other
+                if !options_ended && self.help_command_enabled() && arg == "help" {
+                    self.print_help(ctx);
+                    return Ok(ParseOutcome::HelpShown);
                 }
 
-                return if let Some(command) = subcommands
+                if let Some(command) = subcommands
                     .iter()
-                    .find(|command| command.long_name() == arg)
+                    .find(|command| command.is_named(arg, ctx.settings.case_insensitive))
                 {
-                    command.execute(args)
+                    return descend(self, command, &flags, &params, inherited, args, ctx);
+                } else if let Some(positionals) = positionals {
+                    positionals.push(arg.to_string());
+                    if self.unknown_as_positional() && self.has_command() {
+                        for arg in args.by_ref() {
+                            positionals.push(arg.as_ref().to_string())
+                        }
+                    }
+                } else if let Some(handler) = self.default_subcommand() {
+                    finish_parent(self, &flags, &params, ctx)?;
+                    let rest = args.map(|arg| arg.as_ref().to_string()).collect();
+                    return Ok(ParseOutcome::External(handler, arg.to_string(), rest));
                 } else {
                     // if self.help_enabled() {
                     //     eprintln!("Unknown command: {}", arg);
                     // }
-                    Err(CommandLineError::UnknownCommand(arg.to_string()))
-                };
+                    return Err(CommandLineError::UnknownCommand(arg.to_string()));
+                }
+            }
+        }
+
+        ctx.apply_config(&params)?;
+        ctx.check_required(params.iter().chain(inherited.iter().copied()))?;
+        check_requires(&flags, &params)?;
+        check_one_of(self.required_one_of(), &flags, &params)?;
+
+        if ctx.config_dump_requested {
+            ctx.dump_config(&flags, &params);
+            return Ok(ParseOutcome::ConfigPrinted);
+        }
+
+        if self.has_command() {
+            Ok(ParseOutcome::Dispatch(self))
+        } else if self.list_on_missing() || (no_args && self.empty_args_help()) {
+            self.print_help(ctx);
+            Ok(ParseOutcome::HelpShown)
+        } else {
+            if self.require_subcommand() {
+                let _ = help::write_subcommand_list(self, ctx.err, ctx.settings);
             }
+            Err(CommandLineError::ExpectedSubcommand)
         }
+    }
+}
+
+/// Finishes `current`, whose arguments are `flags` and `params`, and parses the rest of `args` in `command`.
+///
+/// The global parameters of `current` are passed down with the ones it `inherited`, and are only checked for
+/// being required by the final subcommand.
+fn descend<'s, R, T: AsRef<str>>(
+    current: &(impl Command<R> + ?Sized),
+    command: &'s SubCommand<'_, R>,
+    flags: &[Flag],
+    params: &[Parameter],
+    inherited: &[&Parameter],
+    args: impl Iterator<Item = T>,
+    ctx: &mut Context,
+) -> Result<ParseOutcome<'s, R>, CommandLineError> {
+    ctx.depth += 1;
+    if ctx.depth > ctx.settings.max_depth {
+        return Err(CommandLineError::MaxDepthExceeded);
+    }
+    finish_parent(current, flags, params, ctx)?;
+    ctx.trace(&ParseEvent::SubcommandDispatched(command.long_name()));
+    ctx.path.push(command.long_name().to_string());
+    let mut globals = inherited.to_vec();
+    globals.extend(params.iter().filter(|param| param.is_global()));
+    let (globals, excluded): (Vec<_>, Vec<_>) = globals
+        .into_iter()
+        .partition(|param| !command.excluded_globals().contains(&param.long_name()));
+    // an excluded global can no longer be given, so it has to be by now
+    ctx.check_required(excluded)?;
+    command.parse(args, ctx, &globals)
+}
+
+/// Checks the arguments of `current` before parsing moves past it, leaving its global parameters to the final
+/// subcommand.
+fn finish_parent<R>(
+    current: &(impl Command<R> + ?Sized),
+    flags: &[Flag],
+    params: &[Parameter],
+    ctx: &mut Context,
+) -> Result<(), CommandLineError> {
+    ctx.apply_config(params)?;
+    ctx.check_required(params.iter().filter(|param| !param.is_global()))?;
+    check_requires(flags, params)?;
+    check_one_of(current.required_one_of(), flags, params)?;
+    ctx.record_config(flags, params);
+    Ok(())
+}
+
+/// Ensures exactly one of the arguments named by `group` is present on the command line.
+fn check_one_of(
+    group: &[&str],
+    flags: &[Flag],
+    params: &[Parameter],
+) -> Result<(), CommandLineError> {
+    if group.is_empty() {
+        return Ok(());
+    }
+
+    let is_present = |name: &str| match flags.find_by_long_name(name) {
+        Some(flag) => flag.is_present(),
+        None => params
+            .find_by_long_name(name)
+            .is_some_and(|param| param.is_present()),
+    };
+    let present: Vec<String> = group
+        .iter()
+        .filter(|&&name| is_present(name))
+        .map(|name| format!("--{}", name))
+        .collect();
+
+    match present.len() {
+        0 => Err(CommandLineError::MissingOneOf(
+            group.iter().map(|name| format!("--{}", name)).collect(),
+        )),
+        1 => Ok(()),
+        _ => Err(CommandLineError::ConflictingFlags(present)),
+    }
+}
+
+/// Runs the callback of a parsed command.
+fn dispatch<R>(command: &dyn Command<R>) -> R {
+    match command.command() {
+        Some(callback) => callback(),
+        None => panic!("Command only has an async callback, it must be executed asynchronously."),
+    }
+}
+
+/// Awaits the async callback of a parsed command, falling back to its synchronous callback.
+#[cfg(feature = "async")]
+async fn dispatch_async<R>(command: &dyn Command<R>) -> R {
+    match command.async_command() {
+        Some(callback) => callback().await,
+        None => dispatch(command),
+    }
+}
+
+/// Parses a cluster of combined short names (e.g. `-fg`).
+///
+/// Every character must name a short flag, except the last which may name a parameter that takes the next argument as its value.
+/// The first unknown character is reported along with the cluster it came from.
+///
+/// Parsing is transactional: every character is resolved, and the parameter's value taken and stored, before any flag is marked,
+/// so an error leaves every flag untouched.
+fn parse_short_cluster<T: AsRef<str>>(
+    arg: &str,
+    flags: &Lookup<Flag>,
+    params: &Lookup<Parameter>,
+    args: &mut impl Iterator<Item = T>,
+    pending: &mut Option<T>,
+    ctx: &mut Context,
+) -> Result<(), CommandLineError> {
+    let cluster = &arg[1..];
+    let mut cluster_flags = Vec::new();
+    let mut cluster_param = None;
+
+    for (index, ch) in cluster.char_indices() {
+        let name = &cluster[index..index + ch.len_utf8()];
+        let is_last = index + ch.len_utf8() == cluster.len();
 
-        if let Some(command) = command {
-            return Ok(command());
+        if let Some(flag) = flags.find_by_short_name(name) {
+            cluster_flags.push(flag)
+        } else if let (true, Some(param)) = (is_last, params.find_by_short_name(name)) {
+            cluster_param = Some(param)
         } else {
-            return Err(CommandLineError::ExpectedSubcommand);
+            return Err(CommandLineError::UnknownArgument(format!(
+                "-{} in {}",
+                name, arg
+            )));
+        }
+    }
+
+    if let Some(param) = cluster_param {
+        let value = take_value(param, arg, args, pending)?;
+        ctx.set_value(param, &value)?
+    }
+
+    for flag in cluster_flags {
+        ctx.mark(flag)
+    }
+
+    Ok(())
+}
+
+/// Sets a flag given an explicit boolean (e.g. `--example=false` or `-e=0`), if flag values are enabled.
+///
+/// Otherwise fails with [`CommandLineError::FlagTakesNoValue`], rather than reporting `arg` as unknown.
+fn set_flag_value(
+    flag: &Flag,
+    arg: &str,
+    value: &str,
+    ctx: &mut Context,
+) -> Result<(), CommandLineError> {
+    if !ctx.settings.flag_values {
+        return Err(CommandLineError::FlagTakesNoValue(arg.to_string()));
+    }
+    let Some(value) = parse_bool(value) else {
+        return Err(CommandLineError::InvalidValue {
+            name: flag.display_name(),
+            value: value.to_string(),
+            expected: "a boolean (true, false, 1, 0, yes or no)".to_string(),
+        });
+    };
+    ctx.set_flag(flag, value);
+    Ok(())
+}
+
+/// The long form of a slash option (e.g. `--example=value` for `/example:value`), if slash options are enabled and it
+/// names a flag, a parameter or help. Anything else (e.g. the path `/tmp`) is left as it is.
+fn slash_option<R>(
+    arg: &str,
+    flags: &[Flag],
+    params: &[Parameter],
+    inherited: &[&Parameter],
+    ctx: &Context,
+    command: &impl Command<R>,
+) -> Option<String> {
+    if !ctx.settings.slash_options || !arg.is_slash_name() {
+        return None;
+    }
+    let (name, value) = match arg[1..].split_once(':') {
+        Some((name, value)) => (name, Some(value)),
+        None => (&arg[1..], None),
+    };
+    let known = flags.find_by_long_name(name).is_some()
+        || params.find_by_long_name(name).is_some()
+        || inherited.find_by_long_name(name).is_some()
+        || (command.help_flag_enabled() && name == "help");
+    if !known {
+        return None;
+    }
+    Some(match value {
+        Some(value) => format!("--{}={}", name, value),
+        None => format!("--{}", name),
+    })
+}
+
+/// The error for a long parameter given without `=value` when values must be attached, suggesting the form it should
+/// have been given in (e.g. `--output=file` for `--output file`).
+fn missing_equals<T: AsRef<str>>(
+    param: &Parameter,
+    arg: &str,
+    next: Option<T>,
+) -> CommandLineError {
+    let value = match &next {
+        Some(value) if !value.as_ref().is_long_name() && !value.as_ref().is_short_name() => {
+            value.as_ref().to_string()
+        }
+        _ => format!("<{}>", param.value_name()),
+    };
+    CommandLineError::MissingEquals {
+        arg: arg.to_string(),
+        suggestion: format!("{}={}", arg, value),
+    }
+}
+
+/// Takes the value of a parameter given without `=value` from the next argument (e.g. `--example value`).
+///
+/// A parameter with an implicit value only takes the next argument if it is a value the parameter accepts.
+/// Otherwise the next argument is left in `pending` and the implicit value is used.
+fn take_value<T: AsRef<str>>(
+    param: &Parameter,
+    arg: &str,
+    args: &mut impl Iterator<Item = T>,
+    pending: &mut Option<T>,
+) -> Result<String, CommandLineError> {
+    let next = args.next();
+    let Some(implicit_value) = param.implicit_value() else {
+        return match next {
+            Some(value) => Ok(value.as_ref().to_string()),
+            None => Err(CommandLineError::ExpectedValue {
+                arg: arg.to_string(),
+                names: param.display_names(),
+                value_name: param.value_name().to_string(),
+            }),
+        };
+    };
+
+    if let Some(value) = next {
+        let candidate = value.as_ref();
+        if !candidate.is_long_name() && !candidate.is_short_name() && param.allows(candidate) {
+            return Ok(candidate.to_string());
+        }
+        *pending = Some(value);
+    }
+    Ok(implicit_value.to_string())
+}
+
+/// Checks that every present flag and parameter has the companions it requires.
+fn check_requires(flags: &[Flag], params: &[Parameter]) -> Result<(), CommandLineError> {
+    fn check<A: Argument>(
+        a: &A,
+        flags: &[Flag],
+        params: &[Parameter],
+    ) -> Result<(), CommandLineError> {
+        if !a.is_present() {
+            return Ok(());
+        }
+        for &name in a.requires() {
+            let present = match flags.find_by_long_name(name) {
+                Some(flag) => flag.is_present(),
+                None => params
+                    .find_by_long_name(name)
+                    .is_some_and(|param| param.is_present()),
+            };
+            if !present {
+                return Err(CommandLineError::MissingDependency {
+                    flag: a.display_name(),
+                    requires: format!("--{}", name),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    for flag in flags {
+        check(flag, flags, params)?
+    }
+    for param in params {
+        check(param, flags, params)?
+    }
+    Ok(())
+}
+
+/// Checks that no two flags or parameters, and no two subcommands, share a name.
+///
+/// Flags and parameters share one namespace, their aliases and a subcommand's short name and aliases count as names.
+fn check_unique_names<R>(
+    flags: &[Flag],
+    params: &[Parameter],
+    subcommands: &[SubCommand<R>],
+) -> Result<(), BuildError> {
+    let mut names = Vec::new();
+    let short_names = flags
+        .iter()
+        .map(|flag| flag.short_name())
+        .chain(params.iter().map(|param| param.short_name()));
+    let long_names = flags
+        .iter()
+        .flat_map(|flag| core::iter::once(flag.long_name()).chain(flag.aliases().iter().copied()))
+        .chain(params.iter().flat_map(|param| {
+            core::iter::once(param.long_name()).chain(param.aliases().iter().copied())
+        }));
+    names.extend(
+        short_names
+            .filter(|name| !name.is_empty())
+            .map(|name| format!("-{}", name)),
+    );
+    names.extend(
+        long_names
+            .filter(|name| !name.is_empty())
+            .map(|name| format!("--{}", name)),
+    );
+    for subcommand in subcommands {
+        if !subcommand.short_name().is_empty() {
+            names.push(subcommand.short_name().to_string());
         }
+        names.push(subcommand.long_name().to_string());
+        names.extend(subcommand.aliases().iter().map(|alias| alias.to_string()));
     }
+
+    for (index, name) in names.iter().enumerate() {
+        if names[..index].contains(name) {
+            return Err(BuildError::DuplicateName(name.clone()));
+        }
+    }
+    Ok(())
 }
 
+/// Parses an explicit flag value, `true`, `1` and `yes` or `false`, `0` and `no`, ignoring case.
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.to_ascii_lowercase().as_str() {
+        "true" | "1" | "yes" => Some(true),
+        "false" | "0" | "no" => Some(false),
+        _ => None,
+    }
+}
+
+/// Splits `name=value` on the first `=`, so the value keeps any further (or leading) `=` verbatim.
 #[inline(always)]
 fn split_parameter(arg: &str) -> Option<(&str, &str)> {
     if let Some(equals_pos) = arg.find('=') {
@@ -275,12 +1394,113 @@ fn format_parameter_value(value: &str) -> String {
     value.to_string()
 }
 
+/// The long names of `arguments`, in order, leaving out the ones that only have a short name.
+fn long_names<A: Argument>(arguments: &[A]) -> Vec<&str> {
+    arguments
+        .iter()
+        .map(Argument::long_name)
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
 trait Argument {
     fn long_name(&self) -> &str;
     fn short_name(&self) -> &str;
     fn description(&self) -> &str;
+
+    /// The other long names the argument is accepted by.
+    fn aliases(&self) -> &[&str] {
+        &[]
+    }
+
+    /// Whether `name` is the long name or one of the aliases of the argument.
+    fn is_long_named(&self, name: &str) -> bool {
+        self.long_name() == name || self.aliases().contains(&name)
+    }
+
+    /// The extended description shown by the detailed help style, if it differs from the description.
+    fn long_help(&self) -> Option<&str> {
+        None
+    }
+
+    /// The placeholder shown for the argument's value in help, if it takes one.
+    fn value_name(&self) -> Option<&str> {
+        None
+    }
+
+    fn is_deprecated(&self) -> bool {
+        false
+    }
+
+    fn deprecation_message(&self) -> Option<&str> {
+        None
+    }
+
+    /// The long names of arguments that must also be present when this one is.
+    fn requires(&self) -> &[&str] {
+        &[]
+    }
+
+    /// The environment variable the argument falls back to, if any.
+    fn env(&self) -> Option<&str> {
+        None
+    }
+
+    /// The values the argument is restricted to, empty if any value is allowed.
+    fn allowed_values(&self) -> &[&str] {
+        &[]
+    }
+
+    /// The value the argument takes when it is given without one, if any.
+    fn implicit_value(&self) -> Option<&str> {
+        None
+    }
+
+    /// The value the argument takes when it is not given at all, if any.
+    fn default_value(&self) -> Option<&str> {
+        None
+    }
+
+    /// Whether the argument can be given on the command line.
+    fn is_cli_visible(&self) -> bool {
+        true
+    }
+
+    /// Whether the argument was given on the command line.
+    fn is_present(&self) -> bool;
+
+    /// The name used to refer to the argument in error messages (e.g. `--param` or `-p`).
+    fn display_name(&self) -> String {
+        if self.long_name().is_empty() {
+            format!("-{}", self.short_name())
+        } else {
+            format!("--{}", self.long_name())
+        }
+    }
+
+    /// The name identifying the argument, its long name or, if it has none, its short name.
+    fn key_name(&self) -> &str {
+        if self.long_name().is_empty() {
+            self.short_name()
+        } else {
+            self.long_name()
+        }
+    }
+
+    /// Every name of the argument, as listed in help (e.g. `-p, --param`).
+    fn display_names(&self) -> String {
+        match (self.short_name(), self.long_name()) {
+            (short_name, "") => format!("-{}", short_name),
+            ("", long_name) => format!("--{}", long_name),
+            (short_name, long_name) => format!("-{}, --{}", short_name, long_name),
+        }
+    }
 }
 
+/// Resolves arguments by name.
+///
+/// Lookups always resolve to at most one definition. If several arguments are registered with the
+/// same name (a misconfiguration), the first one in registration order wins and the rest are never matched.
 trait FindExt<T> {
     fn find_by_long_name(&self, name: &str) -> Option<&T>;
     fn find_by_short_name(&self, name: &str) -> Option<&T>;
@@ -290,20 +1510,45 @@ impl<T> FindExt<T> for [T]
 where
     T: Argument,
 {
+    // an empty name (e.g. from `--` or `--=value`) never matches an argument that lacks that kind of name, and arguments
+    // that cannot be given on the command line never match
     #[inline(always)]
     fn find_by_long_name(&self, name: &str) -> Option<&T> {
-        self.iter().find(|arg| arg.long_name() == name)
+        self.iter()
+            .find(|arg| !name.is_empty() && arg.is_cli_visible() && arg.is_long_named(name))
     }
 
     #[inline(always)]
     fn find_by_short_name(&self, name: &str) -> Option<&T> {
-        self.iter().find(|arg| arg.short_name() == name)
+        self.iter()
+            .find(|arg| !name.is_empty() && arg.is_cli_visible() && arg.short_name() == name)
+    }
+}
+
+impl<T> FindExt<T> for [&T]
+where
+    T: Argument,
+{
+    #[inline(always)]
+    fn find_by_long_name(&self, name: &str) -> Option<&T> {
+        self.iter()
+            .copied()
+            .find(|arg| !name.is_empty() && arg.is_cli_visible() && arg.is_long_named(name))
+    }
+
+    #[inline(always)]
+    fn find_by_short_name(&self, name: &str) -> Option<&T> {
+        self.iter()
+            .copied()
+            .find(|arg| !name.is_empty() && arg.is_cli_visible() && arg.short_name() == name)
     }
 }
 
 trait IsNameExt {
     fn is_long_name(&self) -> bool;
     fn is_short_name(&self) -> bool;
+    fn is_plus_name(&self) -> bool;
+    fn is_slash_name(&self) -> bool;
 }
 
 impl IsNameExt for str {
@@ -311,8 +1556,36 @@ impl IsNameExt for str {
     fn is_long_name(&self) -> bool {
         self.starts_with("--")
     }
+    /// Negative numbers (e.g. `-5` or `-1.5`) and a lone `-` (conventionally standard input)
+    /// are not short names, so they can be passed as values.
+    ///
+    /// A flag with a numeric short name (e.g. `-1`) is still marked by it, unless the number is taken as the value of
+    /// a parameter (e.g. `--offset -1`).
     #[inline(always)]
     fn is_short_name(&self) -> bool {
-        self.starts_with("-")
+        self.starts_with("-") && self != "-" && !is_number(&self[1..])
+    }
+    /// A `+` prefixed name (e.g. `+example`), used to enable flags when plus/minus flags are enabled.
+    #[inline(always)]
+    fn is_plus_name(&self) -> bool {
+        self.starts_with('+') && self != "+"
     }
+    /// A `/` prefixed name (e.g. `/example` or `/example:value`), used when slash options are enabled. A name with
+    /// another `/` (e.g. `/usr/bin`) is a path.
+    #[inline(always)]
+    fn is_slash_name(&self) -> bool {
+        self.strip_prefix('/')
+            .and_then(|rest| rest.split(':').next())
+            .is_some_and(|name| !name.is_empty() && !name.contains('/'))
+    }
+}
+
+/// Whether `value` matches `\d+(\.\d+)?`.
+fn is_number(value: &str) -> bool {
+    let (whole, fraction) = match value.split_once('.') {
+        Some((whole, fraction)) => (whole, Some(fraction)),
+        None => (value, None),
+    };
+    let is_digits = |digits: &str| !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit());
+    is_digits(whole) && fraction.is_none_or(is_digits)
 }
@@ -0,0 +1,71 @@
+/*
+ * Copyright 2023 Jacob R. Green
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+
+/// The pagers tried when `PAGER` is not set, in order.
+const DEFAULT_PAGERS: &[&str] = &["less -R", "more"];
+
+/// The pager command lines to try, `pager` (the value of `PAGER`) alone if it is set and not blank.
+pub(super) fn pager_commands(pager: Option<&str>) -> Vec<Vec<&str>> {
+    match pager {
+        Some(pager) if !pager.trim().is_empty() => vec![pager.split_whitespace().collect()],
+        _ => DEFAULT_PAGERS
+            .iter()
+            .map(|pager| pager.split_whitespace().collect())
+            .collect(),
+    }
+}
+
+/// Shows `text` in the first pager that can be started, waiting for it to exit.
+///
+/// Fails if none could be started, so the caller can print `text` directly.
+pub(super) fn page(text: &str) -> io::Result<()> {
+    let pager = std::env::var("PAGER").ok();
+    let mut last_error = io::Error::new(io::ErrorKind::NotFound, "No pager was found.");
+
+    for command in pager_commands(pager.as_deref()) {
+        let Some((program, args)) = command.split_first() else {
+            continue;
+        };
+        let mut child = match Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .spawn()
+        {
+            Ok(child) => child,
+            Err(error) => {
+                last_error = error;
+                continue;
+            }
+        };
+
+        if let Some(mut stdin) = child.stdin.take() {
+            match stdin.write_all(text.as_bytes()) {
+                // the pager was quit before reading everything
+                Err(error) if error.kind() != io::ErrorKind::BrokenPipe => {
+                    let _ = child.wait();
+                    return Err(error);
+                }
+                _ => {}
+            }
+        }
+        child.wait()?;
+        return Ok(());
+    }
+    Err(last_error)
+}
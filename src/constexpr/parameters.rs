@@ -14,17 +14,30 @@
  * limitations under the License.
  */
 
-use crate::constexpr::Argument;
+use crate::constexpr::{Argument, BuildError, CommandLineError};
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+/// A user predicate run against a parameter value before it is stored.
+type Validator = fn(&str) -> Result<(), String>;
 
 /// The value that will be set when a parameter is present on the command line.
 pub struct ParameterValue {
-    value: std::cell::UnsafeCell<Option<String>>,
+    value: core::cell::UnsafeCell<Option<String>>,
+    given: core::cell::Cell<bool>,
 }
 
 impl ParameterValue {
     pub const fn new() -> Self {
         Self {
-            value: std::cell::UnsafeCell::new(None),
+            value: core::cell::UnsafeCell::new(None),
+            given: core::cell::Cell::new(false),
         }
     }
 
@@ -32,6 +45,25 @@ impl ParameterValue {
         unsafe { (&*self.value.get()).as_ref().map(|s| s.as_str()) }
     }
 
+    /// Whether the parameter was given on the command line, even without a value (see
+    /// [`with_optional_value`](crate::constexpr::builders::ParameterBuilder::with_optional_value)).
+    ///
+    /// A value from the environment, a value source or the default is only in [`value`](Self::value), use
+    /// [`has_value`](Self::has_value) to know whether there is one from any source.
+    pub fn is_given(&self) -> bool {
+        self.given.get()
+    }
+
+    /// Whether the parameter has a value from any source, the command line or a fallback.
+    pub fn has_value(&self) -> bool {
+        self.value().is_some()
+    }
+
+    /// Whether the value is `-`, which by convention means "read from standard input".
+    pub fn is_stdin(&self) -> bool {
+        self.value() == Some("-")
+    }
+
     fn set_value(&self, value: String) {
         unsafe {
             self.value.get().replace(Some(value));
@@ -39,19 +71,190 @@ impl ParameterValue {
     }
 }
 
+impl Default for ParameterValue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 unsafe impl Sync for ParameterValue {}
 
+/// The entries collected when a map parameter (e.g. `-D KEY=VALUE`) is present on the command line, in order.
+pub struct MapParameterValue {
+    entries: core::cell::UnsafeCell<Vec<(String, String)>>,
+    given: core::cell::Cell<bool>,
+}
+
+impl MapParameterValue {
+    pub const fn new() -> Self {
+        Self {
+            entries: core::cell::UnsafeCell::new(Vec::new()),
+            given: core::cell::Cell::new(false),
+        }
+    }
+
+    pub fn entries(&self) -> &[(String, String)] {
+        unsafe { (*self.entries.get()).as_slice() }
+    }
+
+    /// The value of the last entry for `key`, if any.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries()
+            .iter()
+            .rev()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    fn push(&self, key: String, value: String) {
+        unsafe {
+            (*self.entries.get()).push((key, value));
+        }
+    }
+}
+
+impl Default for MapParameterValue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+unsafe impl Sync for MapParameterValue {}
+
+/// The values collected when a parameter that may be repeated (e.g. `--tag a --tag b`) is present on the command
+/// line, in order.
+pub struct MultiParameterValue {
+    values: core::cell::UnsafeCell<Vec<String>>,
+    given: core::cell::Cell<bool>,
+}
+
+impl MultiParameterValue {
+    pub const fn new() -> Self {
+        Self {
+            values: core::cell::UnsafeCell::new(Vec::new()),
+            given: core::cell::Cell::new(false),
+        }
+    }
+
+    pub fn values(&self) -> &[String] {
+        unsafe { (*self.values.get()).as_slice() }
+    }
+
+    fn extend(&self, values: Vec<String>) {
+        unsafe {
+            (*self.values.get()).extend(values);
+        }
+    }
+}
+
+impl Default for MultiParameterValue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+unsafe impl Sync for MultiParameterValue {}
+
+/// A running integer set by a numeric parameter: a signed value (e.g. `--level +2` or `--level -1`) adjusts it, a bare
+/// number (e.g. `--level 5`) replaces it.
+pub struct NumericParameterValue {
+    value: core::cell::Cell<Option<i64>>,
+    given: core::cell::Cell<bool>,
+}
+
+impl NumericParameterValue {
+    pub const fn new() -> Self {
+        Self {
+            value: core::cell::Cell::new(None),
+            given: core::cell::Cell::new(false),
+        }
+    }
+
+    pub fn value(&self) -> Option<i64> {
+        self.value.get()
+    }
+
+    /// Applies `value`, returning `false` if it is not a number or the result does not fit in an `i64`.
+    fn apply(&self, value: &str) -> bool {
+        let Ok(number) = value.parse::<i64>() else {
+            return false;
+        };
+        let value = if value.starts_with(['+', '-']) {
+            match self.value.get().unwrap_or(0).checked_add(number) {
+                Some(value) => value,
+                None => return false,
+            }
+        } else {
+            number
+        };
+        self.value.set(Some(value));
+        true
+    }
+}
+
+impl Default for NumericParameterValue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+unsafe impl Sync for NumericParameterValue {}
+
+/// Where a parameter stores its values.
+#[derive(Clone, Copy)]
+enum ParameterTarget<'a> {
+    Value(&'a ParameterValue),
+    Map(&'a MapParameterValue),
+    Multi(&'a MultiParameterValue),
+    Numeric(&'a NumericParameterValue),
+}
+
+/// The kind of value a parameter expects, completed accordingly by the
+/// [completion scripts](crate::constexpr::Application::write_completion) of the application.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ValueHint {
+    /// A path to a file (e.g. `_files` in zsh).
+    FilePath,
+    /// A path to a directory (e.g. `_directories` in zsh).
+    DirPath,
+    /// A host name (e.g. `_hosts` in zsh).
+    Hostname,
+    /// Anything else, completed without suggestions.
+    #[default]
+    Other,
+}
+
 /// A command line string parameter.
 ///
 /// # Example
 /// ```bash
-/// $ ./myapp -{short_name} {value} --{long_name}={value}
+/// $ ./myapp -{short_name} {value} --{long_name}={value} --{long_name} {value}
 /// ```
+///
+/// In `--{long_name}={value}` the name ends at the first `=`, everything after it is the value, verbatim
+/// (e.g. `--expr=a=b` sets `a=b` and `--x==y` sets `=y`).
 pub struct Parameter<'a> {
     short_name: &'a str,
     long_name: &'a str,
+    aliases: &'a [&'a str],
     description: &'a str,
-    value: &'a ParameterValue,
+    long_help: Option<&'a str>,
+    deprecated: bool,
+    deprecation_message: Option<&'a str>,
+    value_name: &'a str,
+    requires: &'a [&'a str],
+    allowed_values: &'a [&'a str],
+    implicit_value: Option<&'a str>,
+    default_value: Option<&'a str>,
+    optional_value: bool,
+    required: bool,
+    global: bool,
+    env: Option<&'a str>,
+    cli_visible: bool,
+    value_delimiter: Option<char>,
+    value_hint: ValueHint,
+    value: ParameterTarget<'a>,
+    validator: Option<Validator>,
 }
 
 impl<'a> Parameter<'a> {
@@ -59,13 +262,124 @@ impl<'a> Parameter<'a> {
         ParameterBuilder {
             short_name: None,
             long_name: None,
+            aliases: &[],
             description: None,
+            long_help: None,
+            deprecated: false,
+            deprecation_message: None,
+            value_name: None,
+            requires: &[],
+            allowed_values: &[],
+            implicit_value: None,
+            default_value: None,
+            optional_value: false,
+            required: false,
+            global: false,
+            env: None,
+            cli_visible: true,
+            value_delimiter: None,
+            value_hint: ValueHint::Other,
             parameter: None,
+            validator: None,
         }
     }
 
-    pub(crate) fn set_value(&self, value: String) {
-        self.value.set_value(value);
+    /// Stores a value given on the command line, marking the parameter as [present](Argument::is_present).
+    pub(crate) fn set_value(&self, value: String) -> Result<(), CommandLineError> {
+        self.set_fallback(value)?;
+        self.mark_given();
+        Ok(())
+    }
+
+    /// Stores a value from outside the command line (e.g. the environment or the default), which leaves the
+    /// parameter not [present](Argument::is_present).
+    pub(crate) fn set_fallback(&self, value: String) -> Result<(), CommandLineError> {
+        match self.value {
+            ParameterTarget::Value(target) => {
+                self.validate(&value)?;
+                target.set_value(value)
+            }
+            ParameterTarget::Map(target) => {
+                self.validate(&value)?;
+                let Some((key, entry)) = value.split_once('=') else {
+                    return Err(CommandLineError::InvalidValue {
+                        name: self.display_name(),
+                        value,
+                        expected: "KEY=VALUE".to_string(),
+                    });
+                };
+                target.push(key.to_string(), entry.to_string())
+            }
+            ParameterTarget::Multi(target) => {
+                let values: Vec<String> = match self.value_delimiter {
+                    Some(delimiter) => value
+                        .split(delimiter)
+                        .filter(|segment| !segment.is_empty())
+                        .map(str::to_string)
+                        .collect(),
+                    None => vec![value],
+                };
+                // every value is checked before any is stored, so an error leaves the parameter untouched
+                for value in &values {
+                    self.validate(value)?
+                }
+                target.extend(values)
+            }
+            ParameterTarget::Numeric(target) => {
+                self.validate(&value)?;
+                if !target.apply(&value) {
+                    return Err(CommandLineError::InvalidValue {
+                        name: self.display_name(),
+                        value,
+                        expected: "an integer or a signed increment (e.g. 5, +2 or -1)".to_string(),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Marks the parameter as given on the command line, even without a value.
+    pub(crate) fn mark_given(&self) {
+        match self.value {
+            ParameterTarget::Value(target) => target.given.set(true),
+            ParameterTarget::Map(target) => target.given.set(true),
+            ParameterTarget::Multi(target) => target.given.set(true),
+            ParameterTarget::Numeric(target) => target.given.set(true),
+        }
+    }
+
+    /// Whether the parameter has a value from any source, or was given without one.
+    pub(crate) fn has_value(&self) -> bool {
+        match self.value {
+            ParameterTarget::Value(target) => target.is_given() || target.value().is_some(),
+            ParameterTarget::Map(target) => !target.entries().is_empty(),
+            ParameterTarget::Multi(target) => !target.values().is_empty(),
+            ParameterTarget::Numeric(target) => target.value().is_some(),
+        }
+    }
+
+    /// Checks `value` against the allowed values and the validator, if the parameter has them.
+    pub(crate) fn validate(&self, value: &str) -> Result<(), CommandLineError> {
+        if !self.allows(value) {
+            return Err(CommandLineError::ValidationFailed {
+                name: self.display_name(),
+                message: format!(
+                    "'{}' is not one of {}",
+                    value,
+                    self.allowed_values.join(", ")
+                ),
+            });
+        }
+        if let Some(validator) = self.validator {
+            if let Err(message) = validator(value) {
+                return Err(CommandLineError::ValidationFailed {
+                    name: self.display_name(),
+                    message,
+                });
+            }
+        }
+        Ok(())
     }
 
     pub const fn short_name(&self) -> &str {
@@ -76,9 +390,95 @@ impl<'a> Parameter<'a> {
         self.long_name
     }
 
+    pub const fn aliases(&self) -> &[&str] {
+        self.aliases
+    }
+
     pub const fn description(&self) -> &str {
         self.description
     }
+
+    pub const fn long_help(&self) -> Option<&str> {
+        self.long_help
+    }
+
+    pub const fn value_name(&self) -> &str {
+        self.value_name
+    }
+
+    pub(crate) fn value(&self) -> Option<&str> {
+        match self.value {
+            ParameterTarget::Value(target) => target.value(),
+            ParameterTarget::Map(_) | ParameterTarget::Multi(_) | ParameterTarget::Numeric(_) => {
+                None
+            }
+        }
+    }
+
+    /// The value of a numeric parameter, `None` for other parameters.
+    pub(crate) fn number(&self) -> Option<i64> {
+        match self.value {
+            ParameterTarget::Numeric(target) => target.value(),
+            _ => None,
+        }
+    }
+
+    /// The entries of a map parameter, `None` for other parameters.
+    pub(crate) fn entries(&self) -> Option<&[(String, String)]> {
+        match self.value {
+            ParameterTarget::Map(target) => Some(target.entries()),
+            _ => None,
+        }
+    }
+
+    /// The values of a parameter that may be repeated, `None` for other parameters.
+    pub(crate) fn values(&self) -> Option<&[String]> {
+        match self.value {
+            ParameterTarget::Multi(target) => Some(target.values()),
+            _ => None,
+        }
+    }
+
+    /// Whether `value` is one of the allowed values, if the parameter restricts them.
+    pub(crate) fn allows(&self, value: &str) -> bool {
+        self.allowed_values.is_empty() || self.allowed_values.contains(&value)
+    }
+
+    pub const fn allowed_values(&self) -> &[&str] {
+        self.allowed_values
+    }
+
+    pub const fn implicit_value(&self) -> Option<&str> {
+        self.implicit_value
+    }
+
+    pub const fn default_value(&self) -> Option<&str> {
+        self.default_value
+    }
+
+    pub const fn has_optional_value(&self) -> bool {
+        self.optional_value
+    }
+
+    pub const fn is_required(&self) -> bool {
+        self.required
+    }
+
+    pub const fn is_global(&self) -> bool {
+        self.global
+    }
+
+    pub const fn env(&self) -> Option<&str> {
+        self.env
+    }
+
+    pub const fn is_cli_visible(&self) -> bool {
+        self.cli_visible
+    }
+
+    pub const fn value_hint(&self) -> ValueHint {
+        self.value_hint
+    }
 }
 
 impl Argument for Parameter<'_> {
@@ -90,16 +490,86 @@ impl Argument for Parameter<'_> {
         self.short_name
     }
 
+    fn aliases(&self) -> &[&str] {
+        self.aliases
+    }
+
     fn description(&self) -> &str {
         self.description
     }
+
+    fn long_help(&self) -> Option<&str> {
+        self.long_help
+    }
+
+    fn is_deprecated(&self) -> bool {
+        self.deprecated
+    }
+
+    fn deprecation_message(&self) -> Option<&str> {
+        self.deprecation_message
+    }
+
+    fn value_name(&self) -> Option<&str> {
+        Some(self.value_name)
+    }
+
+    fn requires(&self) -> &[&str] {
+        self.requires
+    }
+
+    fn env(&self) -> Option<&str> {
+        self.env
+    }
+
+    fn allowed_values(&self) -> &[&str] {
+        self.allowed_values
+    }
+
+    fn implicit_value(&self) -> Option<&str> {
+        self.implicit_value
+    }
+
+    fn default_value(&self) -> Option<&str> {
+        self.default_value
+    }
+
+    fn is_cli_visible(&self) -> bool {
+        self.cli_visible
+    }
+
+    fn is_present(&self) -> bool {
+        match self.value {
+            ParameterTarget::Value(target) => target.given.get(),
+            ParameterTarget::Map(target) => target.given.get(),
+            ParameterTarget::Multi(target) => target.given.get(),
+            ParameterTarget::Numeric(target) => target.given.get(),
+        }
+    }
 }
 
 pub struct ParameterBuilder<'a> {
     short_name: Option<&'a str>,
     long_name: Option<&'a str>,
+    aliases: &'a [&'a str],
     description: Option<&'a str>,
-    parameter: Option<&'a ParameterValue>,
+    long_help: Option<&'a str>,
+    deprecated: bool,
+    deprecation_message: Option<&'a str>,
+    value_name: Option<&'a str>,
+    requires: &'a [&'a str],
+    allowed_values: &'a [&'a str],
+    implicit_value: Option<&'a str>,
+    default_value: Option<&'a str>,
+    optional_value: bool,
+    required: bool,
+    global: bool,
+    env: Option<&'a str>,
+    cli_visible: bool,
+    value_delimiter: Option<char>,
+    value_hint: ValueHint,
+    parameter: Option<ParameterTarget<'a>>,
+    validator: Option<Validator>,
 }
 
 impl<'a> ParameterBuilder<'a> {
@@ -113,13 +583,160 @@ impl<'a> ParameterBuilder<'a> {
         self
     }
 
+    /// Sets other long names the parameter is accepted by (e.g. the legacy `colour` for `color`).
+    ///
+    /// Help shows the long name, the detailed style also lists the aliases.
+    pub const fn with_aliases(mut self, aliases: &'a [&'a str]) -> Self {
+        self.aliases = aliases;
+        self
+    }
+
     pub const fn with_description(mut self, description: &'a str) -> Self {
         self.description = Some(description);
         self
     }
 
+    /// Sets an extended description, printed instead of the description by the detailed help style.
+    pub const fn with_long_help(mut self, text: &'a str) -> Self {
+        self.long_help = Some(text);
+        self
+    }
+
+    /// Marks the parameter as deprecated. It still works, but using it prints a warning
+    /// (including `message`, if any) to stderr.
+    pub const fn with_deprecated(mut self, message: Option<&'a str>) -> Self {
+        self.deprecated = true;
+        self.deprecation_message = message;
+        self
+    }
+
+    /// Sets the placeholder shown for the value in help (defaults to `VALUE`).
+    pub const fn with_value_name(mut self, value_name: &'a str) -> Self {
+        self.value_name = Some(value_name);
+        self
+    }
+
+    /// Sets the long names of flags or parameters that must also be present when this parameter is.
+    pub const fn with_requires(mut self, requires: &'a [&'a str]) -> Self {
+        self.requires = requires;
+        self
+    }
+
+    /// Restricts the parameter to one of `values`, any other value aborts parsing with
+    /// [`CommandLineError::ValidationFailed`].
+    pub const fn with_allowed_values(mut self, values: &'a [&'a str]) -> Self {
+        self.allowed_values = values;
+        self
+    }
+
+    /// Sets the value used when the parameter is given without one (e.g. `--color` meaning `--color=always`).
+    ///
+    /// The following argument is only taken as the value if the parameter accepts it.
+    pub const fn with_implicit_value(mut self, value: &'a str) -> Self {
+        self.implicit_value = Some(value);
+        self
+    }
+
+    /// Sets the value used when the parameter is not given at all, the last fallback after its
+    /// [environment variable](Self::with_env), the value sources and the config source.
+    ///
+    /// It is validated like any other value and makes a [required](Self::with_required) parameter always present.
+    pub const fn with_default(mut self, value: &'a str) -> Self {
+        self.default_value = Some(value);
+        self
+    }
+
+    /// Lets the value be omitted: `--color` without `=value` never takes the following argument, so it cannot
+    /// swallow a positional. The parameter is then set to its [implicit value](Self::with_implicit_value), if it has
+    /// one, or only marked as [given](ParameterValue::is_given).
+    ///
+    /// The short form still takes the following argument as the value.
+    pub const fn with_optional_value(mut self, optional: bool) -> Self {
+        self.optional_value = optional;
+        self
+    }
+
+    /// Requires the parameter to be given, parsing fails with [`CommandLineError::MissingRequiredParameter`]
+    /// otherwise (unless [interactive prompts](crate::constexpr::builders::ApplicationBuilder::with_interactive_prompts)
+    /// are enabled).
+    pub const fn with_required(mut self, required: bool) -> Self {
+        self.required = required;
+        self
+    }
+
+    /// Makes the parameter available to every subcommand below the command defining it, so it can be given
+    /// before or after the subcommand (e.g. `myapp --config c sub` or `myapp sub --config c`).
+    ///
+    /// A subcommand argument with the same name takes precedence. A required global parameter is checked once
+    /// the final subcommand has been parsed.
+    pub const fn with_global(mut self, global: bool) -> Self {
+        self.global = global;
+        self
+    }
+
+    /// Falls back to the environment variable `var` when the parameter is not given on the command line.
+    ///
+    /// The variable takes precedence over the [config source](crate::constexpr::builders::ApplicationBuilder::with_config_source),
+    /// and is shown in help (e.g. `(env: APP_TOKEN)`).
+    pub const fn with_env(mut self, var: &'a str) -> Self {
+        self.env = Some(var);
+        self
+    }
+
+    /// Whether the parameter can be given on the command line (defaults to `true`).
+    ///
+    /// A parameter that is not is never matched by the parser, as if it did not exist, but is still set from its
+    /// [environment variable](Self::with_env) and configured value, and is shown in help (e.g. `(env: APP_TOKEN only)`).
+    pub const fn with_cli_visible(mut self, visible: bool) -> Self {
+        self.cli_visible = visible;
+        self
+    }
+
     pub const fn with_parameter(mut self, value: &'a ParameterValue) -> Self {
-        self.parameter = Some(value);
+        self.parameter = Some(ParameterTarget::Value(value));
+        self
+    }
+
+    /// Collects every occurrence of the parameter as a `KEY=VALUE` entry into `map`, instead of setting a
+    /// [`ParameterValue`]. A value without `=` aborts parsing with [`CommandLineError::InvalidValue`].
+    pub const fn with_map_parameter(mut self, map: &'a MapParameterValue) -> Self {
+        self.parameter = Some(ParameterTarget::Map(map));
+        self
+    }
+
+    /// Collects the value of every occurrence of the parameter into `values`, instead of setting a [`ParameterValue`].
+    pub const fn with_multi_parameter(mut self, values: &'a MultiParameterValue) -> Self {
+        self.parameter = Some(ParameterTarget::Multi(values));
+        self
+    }
+
+    /// Sets `value` from the parameter as a number: a signed value (e.g. `+2` or `-1`) is added to it, a bare number
+    /// replaces it. Anything else aborts parsing with [`CommandLineError::InvalidValue`].
+    pub const fn with_numeric_parameter(mut self, value: &'a NumericParameterValue) -> Self {
+        self.parameter = Some(ParameterTarget::Numeric(value));
+        self
+    }
+
+    /// Splits each value of a [multi parameter](Self::with_multi_parameter) on `delimiter` (e.g. `--tags a,b,c`).
+    ///
+    /// Empty segments, including the one after a trailing delimiter, are dropped. Has no effect on other parameters.
+    pub const fn with_value_delimiter(mut self, delimiter: char) -> Self {
+        self.value_delimiter = Some(delimiter);
+        self
+    }
+
+    /// Sets the kind of value the parameter expects (defaults to [`ValueHint::Other`]), completed accordingly by the
+    /// [completion scripts](crate::constexpr::Application::write_completion) of the application.
+    pub const fn with_value_hint(mut self, hint: ValueHint) -> Self {
+        self.value_hint = hint;
+        self
+    }
+
+    /// Sets a predicate that every value must pass before it is stored.
+    ///
+    /// Returning `Err(message)` aborts parsing with [`CommandLineError::ValidationFailed`].
+    pub const fn with_validator(mut self, validator: Validator) -> Self {
+        self.validator = Some(validator);
         self
     }
 
@@ -133,14 +750,34 @@ impl<'a> ParameterBuilder<'a> {
                 Some(long_name) => long_name,
                 None => "",
             },
+            aliases: self.aliases,
             description: match self.description {
                 Some(description) => description,
                 None => "",
             },
+            long_help: self.long_help,
+            deprecated: self.deprecated,
+            deprecation_message: self.deprecation_message,
+            value_name: match self.value_name {
+                Some(value_name) => value_name,
+                None => "VALUE",
+            },
+            requires: self.requires,
+            allowed_values: self.allowed_values,
+            implicit_value: self.implicit_value,
+            default_value: self.default_value,
+            optional_value: self.optional_value,
+            required: self.required,
+            global: self.global,
+            env: self.env,
+            cli_visible: self.cli_visible,
+            value_delimiter: self.value_delimiter,
+            value_hint: self.value_hint,
             value: match self.parameter {
                 Some(value) => value,
                 None => panic!("Parameter must have a value."),
             },
+            validator: self.validator,
         };
         assert!(
             !(param.short_name.is_empty() && param.long_name.is_empty()),
@@ -148,4 +785,15 @@ impl<'a> ParameterBuilder<'a> {
         );
         param
     }
+
+    /// Builds the parameter, reporting a misconfiguration as a [`BuildError`] instead of panicking.
+    pub fn try_build(self) -> Result<Parameter<'a>, BuildError> {
+        if self.parameter.is_none() {
+            return Err(BuildError::MissingValue);
+        }
+        if self.short_name.unwrap_or("").is_empty() && self.long_name.unwrap_or("").is_empty() {
+            return Err(BuildError::MissingName);
+        }
+        Ok(self.build())
+    }
 }
@@ -0,0 +1,121 @@
+/*
+ * Copyright 2023 Jacob R. Green
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+
+/// The values collected from positional arguments on the command line.
+///
+/// # Example
+/// ```bash
+/// $ ./myapp {value} {value}
+/// ```
+pub struct PositionalValue {
+    values: core::cell::UnsafeCell<Vec<String>>,
+    handler: Option<fn(&str)>,
+}
+
+impl PositionalValue {
+    pub const fn new() -> Self {
+        Self {
+            values: core::cell::UnsafeCell::new(Vec::new()),
+            handler: None,
+        }
+    }
+
+    /// Passes every positional argument to `handler` as it is parsed, instead of collecting them.
+    ///
+    /// Nothing is buffered, so arbitrarily many arguments can be streamed through, and [`values`](Self::values)
+    /// stays empty.
+    pub const fn streaming(handler: fn(&str)) -> Self {
+        Self {
+            values: core::cell::UnsafeCell::new(Vec::new()),
+            handler: Some(handler),
+        }
+    }
+
+    pub fn values(&self) -> &[String] {
+        unsafe { (*self.values.get()).as_slice() }
+    }
+
+    pub(crate) fn push(&self, value: String) {
+        match self.handler {
+            Some(handler) => handler(&value),
+            None => unsafe { (*self.values.get()).push(value) },
+        }
+    }
+}
+
+impl Default for PositionalValue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+unsafe impl Sync for PositionalValue {}
+
+/// The most positional arguments a subcommand can name.
+pub const MAX_POSITIONAL_NAMES: usize = 8;
+
+/// A named positional argument, shown in usage and help.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PositionalName<'a> {
+    name: &'a str,
+    required: bool,
+}
+
+impl PositionalName<'_> {
+    pub const fn name(&self) -> &str {
+        self.name
+    }
+
+    pub const fn is_required(&self) -> bool {
+        self.required
+    }
+}
+
+/// The positional arguments named by a command, in order.
+#[derive(Clone, Copy)]
+pub(crate) struct PositionalNames<'a> {
+    names: [PositionalName<'a>; MAX_POSITIONAL_NAMES],
+    len: usize,
+}
+
+impl<'a> PositionalNames<'a> {
+    pub(crate) const fn new() -> Self {
+        Self {
+            names: [PositionalName {
+                name: "",
+                required: false,
+            }; MAX_POSITIONAL_NAMES],
+            len: 0,
+        }
+    }
+
+    pub(crate) const fn push(mut self, name: &'a str, required: bool) -> Self {
+        assert!(
+            self.len < MAX_POSITIONAL_NAMES,
+            "Too many named positional arguments."
+        );
+        self.names[self.len] = PositionalName { name, required };
+        self.len += 1;
+        self
+    }
+
+    pub(crate) const fn as_slice(&self) -> &[PositionalName<'a>] {
+        self.names.split_at(self.len).0
+    }
+}
@@ -0,0 +1,98 @@
+/*
+ * Copyright 2023 Jacob R. Green
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+#[cfg(feature = "std")]
+use super::{CommandLineError, ErrorFormatter};
+
+#[cfg(feature = "std")]
+use super::io::{self, Write};
+
+#[cfg(feature = "std")]
+const RED: &str = "\x1b[1;31m";
+#[cfg(feature = "std")]
+const BOLD: &str = "\x1b[1m";
+#[cfg(feature = "std")]
+const RESET: &str = "\x1b[0m";
+
+/// When errors are printed in color.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorChoice {
+    /// Only when stderr is a terminal and the `NO_COLOR` environment variable is not set.
+    Auto,
+    /// Always, even when stderr is redirected.
+    Always,
+    /// Never.
+    Never,
+}
+
+impl ColorChoice {
+    /// Whether to use color, given whether the output is a terminal and whether `NO_COLOR` is set.
+    #[cfg(feature = "std")]
+    pub(super) const fn enabled(self, is_terminal: bool, no_color: bool) -> bool {
+        match self {
+            ColorChoice::Auto => is_terminal && !no_color,
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+        }
+    }
+}
+
+/// Writes `error` as a single `error: ...` line, with `error:` in red and the offending argument in bold if `color`
+/// is set.
+///
+/// The message is the one `formatter` gives, if there is one, otherwise the English [`Display`](core::fmt::Display)
+/// text.
+#[cfg(feature = "std")]
+pub(super) fn write_error(
+    w: &mut dyn Write,
+    error: &CommandLineError,
+    formatter: Option<ErrorFormatter>,
+    color: bool,
+) -> io::Result<()> {
+    let message = match formatter {
+        Some(formatter) => formatter(error),
+        None => error.to_string(),
+    };
+    if !color {
+        return writeln!(w, "error: {}", message);
+    }
+
+    let message = match offending_argument(error) {
+        Some(arg) if !arg.is_empty() => {
+            message.replacen(arg, &format!("{}{}{}", BOLD, arg, RESET), 1)
+        }
+        _ => message,
+    };
+    writeln!(w, "{}error:{} {}", RED, RESET, message)
+}
+
+/// The argument, name or value that caused `error`, if there is one.
+#[cfg(feature = "std")]
+fn offending_argument(error: &CommandLineError) -> Option<&str> {
+    match error {
+        CommandLineError::UnknownArgument(arg)
+        | CommandLineError::UnexpectedParameter(arg)
+        | CommandLineError::UnknownCommand(arg)
+        | CommandLineError::FlagTakesNoValue(arg)
+        | CommandLineError::MissingRequiredParameter(arg) => Some(arg),
+        CommandLineError::ExpectedValue { arg, .. }
+        | CommandLineError::MissingEquals { arg, .. } => Some(arg),
+        CommandLineError::ValidationFailed { name, .. } => Some(name),
+        CommandLineError::MissingDependency { flag, .. } => Some(flag),
+        CommandLineError::InvalidValue { value, .. } => Some(value),
+        _ => None,
+    }
+}
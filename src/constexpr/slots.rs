@@ -0,0 +1,107 @@
+/*
+ * Copyright 2023 Jacob R. Green
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::*;
+
+/// A single argument recorded by [`parse_into_slots`](Application::parse_into_slots), borrowing both the application
+/// and the arguments instead of allocating.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ArgSlot<'s> {
+    /// Not filled in.
+    #[default]
+    Empty,
+    /// A flag was present, by long name (or short name if it has none).
+    Flag(&'s str),
+    /// A parameter was given a value, by long name (or short name if it has none).
+    Param { name: &'s str, value: &'s str },
+    /// A positional argument.
+    Positional(&'s str),
+}
+
+/// Parses the arguments of `app` itself into `slots`, in order, returning how many were filled. Subcommands are
+/// arguments like any other.
+///
+/// Values are never stored in the application, validated parameters are checked but no other processing happens.
+pub(super) fn parse_into_slots<'s, R>(
+    app: &'s Application<'_, R>,
+    args: &[&'s str],
+    slots: &mut [ArgSlot<'s>],
+) -> Result<usize, CommandLineError> {
+    let flags = app.flags();
+    let params = app.parameters();
+
+    let mut filled = 0;
+    let mut options_ended = false;
+    let mut args = args.iter().copied();
+
+    while let Some(arg) = args.next() {
+        let slot = if options_ended || !(arg.is_long_name() || arg.is_short_name()) {
+            ArgSlot::Positional(arg)
+        } else if arg == "--" {
+            options_ended = true;
+            continue;
+        } else {
+            let (name, attached) = match arg.strip_prefix("--") {
+                Some(long) => match split_parameter(long) {
+                    Some((name, value)) => (name, Some(value)),
+                    None => (long, None),
+                },
+                None => (&arg[1..], None),
+            };
+            let long = arg.is_long_name();
+            let flag = match long {
+                true => flags.find_by_long_name(name),
+                false => flags.find_by_short_name(name),
+            };
+            let param = match long {
+                true => params.find_by_long_name(name),
+                false => params.find_by_short_name(name),
+            };
+            match (flag, param, attached) {
+                (Some(flag), _, None) => ArgSlot::Flag(flag.key_name()),
+                (Some(_), _, Some(_)) => {
+                    return Err(CommandLineError::FlagTakesNoValue(arg.to_string()))
+                }
+                (None, Some(param), attached) => {
+                    let Some(value) = attached.or_else(|| args.next()) else {
+                        return Err(CommandLineError::ExpectedValue {
+                            arg: arg.to_string(),
+                            names: param.display_names(),
+                            value_name: param.value_name().to_string(),
+                        });
+                    };
+                    param.validate(value)?;
+                    ArgSlot::Param {
+                        name: param.key_name(),
+                        value,
+                    }
+                }
+                (None, None, _) => return Err(CommandLineError::UnknownArgument(arg.to_string())),
+            }
+        };
+
+        let Some(target) = slots.get_mut(filled) else {
+            return Err(CommandLineError::ParseError(format!(
+                "More than {} arguments",
+                slots.len()
+            )));
+        };
+        *target = slot;
+        filled += 1;
+    }
+
+    Ok(filled)
+}
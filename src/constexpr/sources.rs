@@ -0,0 +1,85 @@
+/*
+ * Copyright 2023 Jacob R. Green
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+#[cfg(not(feature = "std"))]
+use alloc::string::{String, ToString};
+
+/// A layer of configured parameter values, queried by long name for every parameter not given on the command line.
+///
+/// Sources are set with [`with_value_sources`](crate::constexpr::builders::ApplicationBuilder::with_value_sources)
+/// and queried in order until one has a value. Any `fn(&str) -> Option<String>` or closure is a source.
+pub trait ValueSource {
+    /// The value configured for the parameter with the long name `name`, if any.
+    fn value(&self, name: &str) -> Option<String>;
+}
+
+impl<F: Fn(&str) -> Option<String>> ValueSource for F {
+    fn value(&self, name: &str) -> Option<String> {
+        self(name)
+    }
+}
+
+/// Reads parameters from environment variables named after their long name, upper cased with `-` replaced by `_`
+/// and prefixed with `{prefix}_` (e.g. `APP_OUTPUT_DIR` for `--output-dir`).
+#[cfg(feature = "std")]
+pub struct EnvSource<'a> {
+    prefix: &'a str,
+}
+
+#[cfg(feature = "std")]
+impl<'a> EnvSource<'a> {
+    /// An environment source for variables starting with `{prefix}_`, or just the name if `prefix` is empty.
+    pub const fn new(prefix: &'a str) -> Self {
+        Self { prefix }
+    }
+
+    /// The variable read for the parameter with the long name `name`.
+    pub fn var_name(&self, name: &str) -> String {
+        let name = name.to_uppercase().replace('-', "_");
+        match self.prefix.is_empty() {
+            true => name,
+            false => format!("{}_{}", self.prefix, name),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl ValueSource for EnvSource<'_> {
+    fn value(&self, name: &str) -> Option<String> {
+        std::env::var(self.var_name(name)).ok()
+    }
+}
+
+/// Fixed values by long name, usually the last source as the defaults of the application.
+pub struct DefaultSource<'a> {
+    values: &'a [(&'a str, &'a str)],
+}
+
+impl<'a> DefaultSource<'a> {
+    /// A source of `values`, pairs of a long name and its value, the first of a name wins.
+    pub const fn new(values: &'a [(&'a str, &'a str)]) -> Self {
+        Self { values }
+    }
+}
+
+impl ValueSource for DefaultSource<'_> {
+    fn value(&self, name: &str) -> Option<String> {
+        self.values
+            .iter()
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value.to_string())
+    }
+}
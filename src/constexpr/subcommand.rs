@@ -23,67 +23,237 @@ use super::*;
 /// $ ./myapp {subcommand} --{flag} --{parameter}={value}
 /// ```
 pub struct SubCommand<'a, R = ()> {
-    //short_name: &'a str,
+    short_name: &'a str,
     long_name: &'a str,
+    aliases: &'a [&'a str],
+    category: Option<&'a str>,
     description: &'a str,
     flags: &'a [Flag<'a>],
     params: &'a [Parameter<'a>],
     subcommands: &'a [SubCommand<'a, R>],
+    owned_flags: Vec<Flag<'a>>,
+    owned_params: Vec<Parameter<'a>>,
+    owned_subcommands: Vec<SubCommand<'a, R>>,
+    positionals: Option<&'a PositionalValue>,
+    required_one_of: &'a [&'a str],
+    positional_names: PositionalNames<'a>,
     command: Option<Callback<'a, R>>,
-    help: bool,
+    #[cfg(feature = "async")]
+    async_command: Option<AsyncCallback<'a, R>>,
+    help_command: bool,
+    help_flag: bool,
+    list_on_missing: bool,
+    after_help: Option<&'a str>,
+    version: Option<&'a str>,
+    excluded_globals: &'a [&'a str],
+    help_labels: Option<&'a HelpLabels<'a>>,
 }
 
 impl<'a, R> SubCommand<'a, R> {
     pub const fn build() -> SubCommandBuilder<'a, R> {
         SubCommandBuilder {
+            short_name: None,
             long_name: None,
+            aliases: &[],
+            category: None,
             description: None,
             flags: None,
             params: None,
             subcommands: None,
+            owned_flags: Vec::new(),
+            owned_params: Vec::new(),
+            owned_subcommands: Vec::new(),
+            positionals: None,
+            required_one_of: &[],
+            positional_names: PositionalNames::new(),
             command: None,
-            help: true,
+            #[cfg(feature = "async")]
+            async_command: None,
+            help_command: true,
+            help_flag: true,
+            list_on_missing: false,
+            after_help: None,
+            version: None,
+            excluded_globals: &[],
+            help_labels: None,
         }
     }
 
+    pub const fn short_name(&self) -> &str {
+        self.short_name
+    }
+
     pub const fn long_name(&self) -> &str {
         self.long_name
     }
 
+    pub const fn aliases(&self) -> &[&str] {
+        self.aliases
+    }
+
+    pub const fn category(&self) -> Option<&str> {
+        self.category
+    }
+
+    pub const fn excluded_globals(&self) -> &[&str] {
+        self.excluded_globals
+    }
+
+    /// Whether `name` is the short name, the long name or one of the aliases of the subcommand.
+    pub(super) fn is_named(&self, name: &str, case_insensitive: bool) -> bool {
+        let matches = |candidate: &str| {
+            if case_insensitive {
+                candidate.eq_ignore_ascii_case(name)
+            } else {
+                candidate == name
+            }
+        };
+        (!self.short_name.is_empty() && matches(self.short_name))
+            || matches(self.long_name)
+            || self.aliases.iter().any(|alias| matches(alias))
+    }
+
     pub const fn description(&self) -> &str {
         self.description
     }
 
-    pub const fn flags(&self) -> &[Flag] {
-        self.flags
+    pub const fn flags(&self) -> &[Flag<'_>] {
+        if self.owned_flags.is_empty() {
+            self.flags
+        } else {
+            self.owned_flags.as_slice()
+        }
+    }
+
+    pub const fn parameters(&self) -> &[Parameter<'_>] {
+        if self.owned_params.is_empty() {
+            self.params
+        } else {
+            self.owned_params.as_slice()
+        }
+    }
+
+    pub const fn subcommands(&self) -> &[SubCommand<'_, R>] {
+        if self.owned_subcommands.is_empty() {
+            self.subcommands
+        } else {
+            self.owned_subcommands.as_slice()
+        }
+    }
+
+    /// Panics unless the subcommand has a command to run or a subcommand to select.
+    const fn assert_runnable(&self) {
+        #[cfg(feature = "async")]
+        let has_command = self.command.is_some() || self.async_command.is_some();
+        #[cfg(not(feature = "async"))]
+        let has_command = self.command.is_some();
+        assert!(
+            has_command || !self.subcommands().is_empty(),
+            "Subcommand must have either a default command or at least one subcommand."
+        );
+    }
+
+    pub const fn positionals(&self) -> Option<&PositionalValue> {
+        self.positionals
+    }
+
+    pub const fn positional_names(&self) -> &[PositionalName<'_>] {
+        self.positional_names.as_slice()
+    }
+
+    /// The long names of the subcommands, in order, without their aliases.
+    pub fn subcommand_names(&self) -> Vec<&str> {
+        self.subcommands()
+            .iter()
+            .map(SubCommand::long_name)
+            .collect()
+    }
+
+    /// The long names of the flags, in order, leaving out flags that only have a short name.
+    pub fn flag_long_names(&self) -> Vec<&str> {
+        long_names(self.flags())
+    }
+
+    /// The long names of the parameters, in order, leaving out parameters that only have a short name.
+    pub fn parameter_long_names(&self) -> Vec<&str> {
+        long_names(self.parameters())
+    }
+
+    /// Renders the full help of the subcommand, as printed by `--help`, in the default help style.
+    pub fn render_help(&self) -> String {
+        help::render_help(self, &Settings::new())
+    }
+
+    /// Renders the full help of the subcommand laid out as `options` say, e.g. to embed it in another interface.
+    pub fn format_help(&self, options: HelpOptions) -> String {
+        help::format_help(self, &options)
     }
 
-    pub const fn parameters(&self) -> &[Parameter] {
-        self.params
+    /// Reads the current value of every flag and parameter, keyed by long name (or short name if it has none).
+    ///
+    /// Positional arguments, if collected, are included under [`POSITIONALS_KEY`].
+    pub fn collect_values(&self) -> Map<String, ArgValue> {
+        values::collect_values(self)
     }
 
-    pub const fn subcommands(&self) -> &[SubCommand<R>] {
-        self.subcommands
+    /// Describes the current value of every flag and parameter for debugging, one per line (e.g. `verbose: false`
+    /// or `output: None`).
+    pub fn debug_state(&self) -> String {
+        values::debug_state(self)
     }
 }
 
 pub struct SubCommandBuilder<'a, R> {
-    //short_name: &'a str,
+    short_name: Option<&'a str>,
     long_name: Option<&'a str>,
+    aliases: &'a [&'a str],
+    category: Option<&'a str>,
     description: Option<&'a str>,
     flags: Option<&'a [Flag<'a>]>,
     params: Option<&'a [Parameter<'a>]>,
     subcommands: Option<&'a [SubCommand<'a, R>]>,
+    owned_flags: Vec<Flag<'a>>,
+    owned_params: Vec<Parameter<'a>>,
+    owned_subcommands: Vec<SubCommand<'a, R>>,
+    positionals: Option<&'a PositionalValue>,
+    required_one_of: &'a [&'a str],
+    positional_names: PositionalNames<'a>,
     command: Option<Callback<'a, R>>,
-    help: bool,
+    #[cfg(feature = "async")]
+    async_command: Option<AsyncCallback<'a, R>>,
+    help_command: bool,
+    help_flag: bool,
+    list_on_missing: bool,
+    after_help: Option<&'a str>,
+    version: Option<&'a str>,
+    excluded_globals: &'a [&'a str],
+    help_labels: Option<&'a HelpLabels<'a>>,
 }
 
 impl<'a, R> SubCommandBuilder<'a, R> {
+    /// Sets the canonical short form of the subcommand (e.g. `b` for `build`), listed before the long name in help.
+    pub const fn with_short_name(mut self, short_name: &'a str) -> Self {
+        self.short_name = Some(short_name);
+        self
+    }
+
     pub const fn with_long_name(mut self, long_name: &'a str) -> Self {
         self.long_name = Some(long_name);
         self
     }
 
+    /// Sets other names the subcommand can be invoked by (e.g. `co` for `checkout`).
+    pub const fn with_aliases(mut self, aliases: &'a [&'a str]) -> Self {
+        self.aliases = aliases;
+        self
+    }
+
+    /// Lists the subcommand in help under a `name:` header, together with the other subcommands in the category.
+    pub const fn with_category(mut self, name: &'a str) -> Self {
+        self.category = Some(name);
+        self
+    }
+
     pub const fn with_description(mut self, description: &'a str) -> Self {
         self.description = Some(description);
         self
@@ -104,22 +274,158 @@ impl<'a, R> SubCommandBuilder<'a, R> {
         self
     }
 
+    /// Sets flags assembled at runtime, owned by the subcommand. These take precedence over
+    /// [`with_flags`](Self::with_flags).
+    pub fn with_owned_flags(mut self, flags: Vec<Flag<'a>>) -> Self {
+        self.owned_flags = flags;
+        self
+    }
+
+    /// Sets parameters assembled at runtime, owned by the subcommand. These take precedence over
+    /// [`with_parameters`](Self::with_parameters).
+    pub fn with_owned_parameters(mut self, params: Vec<Parameter<'a>>) -> Self {
+        self.owned_params = params;
+        self
+    }
+
+    /// Sets subcommands assembled at runtime, owned by the subcommand. These take precedence over
+    /// [`with_subcommands`](Self::with_subcommands).
+    pub fn with_owned_subcommands(mut self, subcommands: Vec<SubCommand<'a, R>>) -> Self {
+        self.owned_subcommands = subcommands;
+        self
+    }
+
+    /// Collects arguments that are not flags, parameters or subcommands into `positionals`.
+    pub const fn with_positionals(mut self, positionals: &'a PositionalValue) -> Self {
+        self.positionals = Some(positionals);
+        self
+    }
+
+    /// Requires exactly one of the flags or parameters with the long names `group` to be given (e.g. `--stdin`,
+    /// `--file` or `--url`), reported as [`CommandLineError::MissingOneOf`] or [`CommandLineError::ConflictingFlags`].
+    ///
+    /// Only the command line counts, a member with a default or configured value is not given.
+    pub const fn with_required_one_of(mut self, group: &'a [&'a str]) -> Self {
+        self.required_one_of = group;
+        self
+    }
+
+    /// Names the next positional argument in usage and help, e.g. `<SRC>` if `required`, otherwise `[SRC]`.
+    ///
+    /// Up to [`MAX_POSITIONAL_NAMES`] positional arguments can be named.
+    pub const fn with_positional(mut self, name: &'a str, required: bool) -> Self {
+        self.positional_names = self.positional_names.push(name, required);
+        self
+    }
+
     pub const fn with_command(mut self, command: Callback<'a, R>) -> Self {
         self.command = Some(command);
         self
     }
 
+    /// Sets a callback returning a future that is awaited when dispatched asynchronously.
+    #[cfg(feature = "async")]
+    pub const fn with_async_command(mut self, command: AsyncCallback<'a, R>) -> Self {
+        self.async_command = Some(command);
+        self
+    }
+
+    /// Enables or disables both the `help` command keyword and the `-h`/`--help` flag.
     pub const fn with_help(mut self, enabled: bool) -> Self {
-        self.help = enabled;
+        self.help_command = enabled;
+        self.help_flag = enabled;
+        self
+    }
+
+    /// Enables or disables only the built-in `help` command keyword.
+    pub const fn with_help_command(mut self, enabled: bool) -> Self {
+        self.help_command = enabled;
+        self
+    }
+
+    /// Overrides the section titles used in help.
+    pub const fn with_help_labels(mut self, labels: &'a HelpLabels<'a>) -> Self {
+        self.help_labels = Some(labels);
+        self
+    }
+
+    /// Sets text printed verbatim at the end of help, after all sections.
+    pub const fn with_after_help(mut self, text: &'a str) -> Self {
+        self.after_help = Some(text);
+        self
+    }
+
+    /// Sets the version printed by `--version` for this subcommand and those under it, instead of the one inherited
+    /// from the application (e.g. for a plugin provided subcommand).
+    pub const fn with_version(mut self, version: &'a str) -> Self {
+        self.version = Some(version);
+        self
+    }
+
+    /// Sets the long names of global parameters the subcommand does not inherit, rejected as unknown once parsing has
+    /// descended into it (e.g. a `--color` that makes no sense for `export`). The subcommands under it do not inherit
+    /// them either.
+    pub const fn with_excluded_globals(mut self, names: &'a [&'a str]) -> Self {
+        self.excluded_globals = names;
+        self
+    }
+
+    /// Enables or disables only the built-in `-h`/`--help` flag.
+    pub const fn with_help_flag(mut self, enabled: bool) -> Self {
+        self.help_flag = enabled;
+        self
+    }
+
+    /// When enabled and no subcommand is given, the subcommand's help (listing its subcommands)
+    /// is printed instead of returning [`CommandLineError::ExpectedSubcommand`].
+    pub const fn with_list_on_missing(mut self, enabled: bool) -> Self {
+        self.list_on_missing = enabled;
         self
     }
 
+    /// Builds the subcommand. Use [`build_owned`](Self::build_owned) if it has owned flags, parameters or
+    /// subcommands.
     pub const fn build(self) -> SubCommand<'a, R> {
+        assert!(
+            self.owned_flags.is_empty()
+                && self.owned_params.is_empty()
+                && self.owned_subcommands.is_empty(),
+            "Subcommand with owned flags, parameters or subcommands must be built with build_owned."
+        );
+        let subcommand = self.assemble();
+        subcommand.assert_runnable();
+        subcommand
+    }
+
+    /// Builds the subcommand, moving in the lists set with the `with_owned_*` methods (e.g.
+    /// [`with_owned_flags`](Self::with_owned_flags)). Unlike [`build`](Self::build), it cannot be used in a const.
+    pub fn build_owned(mut self) -> SubCommand<'a, R> {
+        let owned_flags = core::mem::take(&mut self.owned_flags);
+        let owned_params = core::mem::take(&mut self.owned_params);
+        let owned_subcommands = core::mem::take(&mut self.owned_subcommands);
         let subcommand = SubCommand {
+            owned_flags,
+            owned_params,
+            owned_subcommands,
+            ..self.assemble()
+        };
+        subcommand.assert_runnable();
+        subcommand
+    }
+
+    /// Copies the settings of the builder into a subcommand with no owned lists, which must already have been taken.
+    const fn assemble(self) -> SubCommand<'a, R> {
+        let subcommand = SubCommand {
+            short_name: match self.short_name {
+                Some(short_name) => short_name,
+                None => "",
+            },
             long_name: match self.long_name {
                 Some(long_name) => long_name,
                 None => panic!("Subcommand must have a long name."),
             },
+            aliases: self.aliases,
+            category: self.category,
             description: match self.description {
                 Some(description) => description,
                 None => "",
@@ -136,17 +442,52 @@ impl<'a, R> SubCommandBuilder<'a, R> {
                 Some(subcommands) => subcommands,
                 None => &[],
             },
+            owned_flags: Vec::new(),
+            owned_params: Vec::new(),
+            owned_subcommands: Vec::new(),
+            positionals: self.positionals,
+            required_one_of: self.required_one_of,
+            positional_names: self.positional_names,
             command: self.command,
-            help: self.help,
+            #[cfg(feature = "async")]
+            async_command: self.async_command,
+            help_command: self.help_command,
+            help_flag: self.help_flag,
+            list_on_missing: self.list_on_missing,
+            after_help: self.after_help,
+            version: self.version,
+            excluded_globals: self.excluded_globals,
+            help_labels: self.help_labels,
         };
-
-        assert!(
-            subcommand.command.is_some() || !subcommand.subcommands.is_empty(),
-            "Subcommand must have either a default command or at least one subcommand."
-        );
-
+        // the owned lists are empty, so forgetting the builder, which cannot be dropped in a const fn, leaks nothing
+        core::mem::forget(self);
         subcommand
     }
+
+    /// Builds the subcommand, reporting a misconfiguration as a [`BuildError`] instead of panicking.
+    ///
+    /// Unlike [`build`](Self::build), flags, parameters or nested subcommands sharing a name are also reported.
+    pub fn try_build(self) -> Result<SubCommand<'a, R>, BuildError> {
+        if self.long_name.unwrap_or("").is_empty() {
+            return Err(BuildError::MissingName);
+        }
+        #[cfg(feature = "async")]
+        let has_command = self.command.is_some() || self.async_command.is_some();
+        #[cfg(not(feature = "async"))]
+        let has_command = self.command.is_some();
+        let has_subcommands =
+            self.subcommands.is_some_and(|s| !s.is_empty()) || !self.owned_subcommands.is_empty();
+        if !has_command && !has_subcommands {
+            return Err(BuildError::NoCommandOrSubcommand);
+        }
+        let subcommand = self.build_owned();
+        check_unique_names(
+            subcommand.flags(),
+            subcommand.parameters(),
+            subcommand.subcommands(),
+        )?;
+        Ok(subcommand)
+    }
 }
 
 impl<R> Command<R> for SubCommand<'_, R> {
@@ -158,23 +499,60 @@ impl<R> Command<R> for SubCommand<'_, R> {
         self.description
     }
 
-    fn flags(&self) -> &[Flag] {
-        self.flags
+    fn flags(&self) -> &[Flag<'_>] {
+        self.flags()
+    }
+
+    fn parameters(&self) -> &[Parameter<'_>] {
+        self.parameters()
     }
 
-    fn parameters(&self) -> &[Parameter] {
-        self.params
+    fn subcommands(&self) -> &[SubCommand<'_, R>] {
+        self.subcommands()
     }
 
-    fn subcommands(&self) -> &[SubCommand<R>] {
-        self.subcommands
+    fn positionals(&self) -> Option<&PositionalValue> {
+        self.positionals
     }
 
-    fn command(&self) -> Option<Callback<R>> {
+    fn required_one_of(&self) -> &[&str] {
+        self.required_one_of
+    }
+
+    fn positional_names(&self) -> &[PositionalName<'_>] {
+        self.positional_names()
+    }
+
+    fn command(&self) -> Option<Callback<'_, R>> {
         self.command
     }
 
-    fn help_enabled(&self) -> bool {
-        self.help
+    #[cfg(feature = "async")]
+    fn async_command(&self) -> Option<AsyncCallback<'_, R>> {
+        self.async_command
+    }
+
+    fn help_command_enabled(&self) -> bool {
+        self.help_command
+    }
+
+    fn help_flag_enabled(&self) -> bool {
+        self.help_flag
+    }
+
+    fn after_help(&self) -> Option<&str> {
+        self.after_help
+    }
+
+    fn version(&self) -> Option<&str> {
+        self.version
+    }
+
+    fn help_labels(&self) -> Option<&HelpLabels<'_>> {
+        self.help_labels
+    }
+
+    fn list_on_missing(&self) -> bool {
+        self.list_on_missing
     }
 }
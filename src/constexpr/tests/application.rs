@@ -0,0 +1,554 @@
+use super::*;
+use std::time::Duration;
+
+#[test]
+fn application_timing() {
+    let app: Application = Application::build()
+        .with_command(&default_command)
+        .with_timing(true)
+        .build();
+
+    assert_eq!(app.last_parse_duration(), None);
+
+    execute_captured(&app, &[]).result.unwrap();
+
+    assert!(app
+        .last_parse_duration()
+        .is_some_and(|d| d >= Duration::ZERO));
+}
+
+#[test]
+fn application_timing_threads() {
+    static APP: Application = Application::build()
+        .with_command(&default_command)
+        .with_timing(true)
+        .build();
+
+    std::thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| execute_captured(&APP, &[]).result.unwrap());
+        }
+    });
+
+    assert!(APP.last_parse_duration().is_some());
+}
+
+#[test]
+fn application_timing_disabled() {
+    let app: Application = Application::build().with_command(&default_command).build();
+
+    execute_captured(&app, &[]).result.unwrap();
+
+    assert_eq!(app.last_parse_duration(), None);
+}
+
+thread_local! {
+    static EVENTS: std::cell::RefCell<Vec<String>> = const { std::cell::RefCell::new(Vec::new()) };
+}
+
+fn record(event: &ParseEvent) {
+    EVENTS.with(|events| events.borrow_mut().push(format!("{:?}", event)));
+}
+
+fn take_events() -> Vec<String> {
+    EVENTS.with(|events| events.take())
+}
+
+#[test]
+fn application_trace() {
+    let f = FlagValue::new();
+    let p = ParameterValue::new();
+    let v = FlagValue::new();
+
+    let flags = &[Flag::build()
+        .with_short_name("f")
+        .with_long_name("flag")
+        .with_description("A flag")
+        .with_flag(&f)
+        .build()];
+
+    let parameters = &[Parameter::build()
+        .with_short_name("p")
+        .with_description("A short parameter")
+        .with_parameter(&p)
+        .build()];
+
+    let subcommand_flags = &[Flag::build()
+        .with_long_name("verbose")
+        .with_description("Prints more")
+        .with_flag(&v)
+        .build()];
+
+    let subcommands = &[SubCommand::build()
+        .with_long_name("sub")
+        .with_flags(subcommand_flags)
+        .with_command(&default_command)
+        .build()];
+
+    let app: Application = Application::build()
+        .with_flags(flags)
+        .with_parameters(parameters)
+        .with_subcommands(subcommands)
+        .with_command(&default_command)
+        .with_trace(record)
+        .build();
+
+    take_events();
+    execute_captured(&app, &["-f", "-p", "value", "sub", "--verbose"])
+        .result
+        .unwrap();
+
+    assert_eq!(
+        take_events(),
+        [
+            r#"FlagMarked("flag")"#,
+            r#"ParameterSet { name: "p", value: "value" }"#,
+            r#"SubcommandDispatched("sub")"#,
+            r#"FlagMarked("verbose")"#,
+        ]
+    );
+
+    assert!(execute_captured(&app, &["--unknown"]).result.is_err());
+
+    assert_eq!(take_events(), [r#"Error(UnknownArgument("--unknown"))"#]);
+}
+
+#[test]
+fn application_json_trace() {
+    use std::sync::Mutex;
+
+    static TRACE: Mutex<Vec<u8>> = Mutex::new(Vec::new());
+    static VERBOSE: FlagValue = FlagValue::new();
+    static NAME: ParameterValue = ParameterValue::new();
+    static FLAGS: &[Flag] = &[Flag::build()
+        .with_long_name("verbose")
+        .with_flag(&VERBOSE)
+        .build()];
+    static PARAMETERS: &[Parameter] = &[Parameter::build()
+        .with_long_name("name")
+        .with_parameter(&NAME)
+        .build()];
+    static APP: Application = Application::build()
+        .with_flags(FLAGS)
+        .with_parameters(PARAMETERS)
+        .with_command(&default_command)
+        .with_json_trace(&TRACE)
+        .build();
+
+    execute_captured(&APP, &["--verbose", "--name", "say \"hi\""])
+        .result
+        .unwrap();
+    assert!(execute_captured(&APP, &["--unknown"]).result.is_err());
+
+    let trace = String::from_utf8(TRACE.lock().unwrap().clone()).unwrap();
+    assert_eq!(
+        trace.lines().collect::<Vec<_>>(),
+        [
+            r#"{"event":"FlagMarked","name":"verbose"}"#,
+            r#"{"event":"ParameterSet","name":"name","value":"say \"hi\""}"#,
+            r#"{"event":"Error","message":"Unknown argument: --unknown"}"#,
+        ]
+    );
+}
+
+#[test]
+fn application_execute_no_exit() {
+    let app: Application = Application::build().with_command(&default_command).build();
+
+    assert_eq!(
+        app.execute_no_exit(["--help"].iter()).unwrap(),
+        RunOutcome::HelpShown
+    );
+    assert_eq!(
+        app.execute_no_exit(["help"].iter()).unwrap(),
+        RunOutcome::HelpShown
+    );
+    assert_eq!(
+        app.execute_no_exit(std::iter::empty::<&str>()).unwrap(),
+        RunOutcome::Completed(())
+    );
+}
+
+#[test]
+fn application_owned_arguments() {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    // Names only known at runtime, borrowed rather than leaked.
+    let names: Vec<String> = ["verbose", "output", "build"]
+        .iter()
+        .map(|name| name.to_string())
+        .collect();
+    let verbose = FlagValue::new();
+    let output = ParameterValue::new();
+    let built = AtomicBool::new(false);
+    let build_command = || built.store(true, Ordering::Relaxed);
+
+    let app: Application = Application::build()
+        .with_command(&default_command)
+        .with_owned_flags(vec![Flag::build()
+            .with_long_name(&names[0])
+            .with_flag(&verbose)
+            .build()])
+        .with_owned_parameters(vec![Parameter::build()
+            .with_long_name(&names[1])
+            .with_parameter(&output)
+            .build()])
+        .with_owned_subcommands(vec![SubCommand::build()
+            .with_long_name(&names[2])
+            .with_command(&build_command)
+            .build()])
+        .build_owned();
+
+    assert!(capture_help(&app, &app).contains("--verbose"));
+
+    let captured = execute_captured(&app, &["--verbose", "--output", "out.txt", "build"]);
+    assert!(captured.result.is_ok());
+    assert!(verbose.value());
+    assert_eq!(output.value(), Some("out.txt"));
+    assert!(built.load(Ordering::Relaxed));
+}
+
+#[test]
+fn application_run_from() {
+    let f = FlagValue::new();
+
+    let flags = &[Flag::build()
+        .with_long_name("flag")
+        .with_description("A flag")
+        .with_flag(&f)
+        .build()];
+
+    let app: Application = Application::build()
+        .with_flags(flags)
+        .with_command(&default_command)
+        .build();
+
+    // nothing is skipped, so a binary path is an unexpected argument
+    assert!(matches!(
+        app.run_from(["./app".to_string(), "--flag".to_string()]),
+        Err(CommandLineError::UnknownCommand(arg)) if arg == "./app"
+    ));
+    assert!(!f.value());
+
+    app.run_from(["--flag".to_string()]).unwrap();
+    assert!(f.value());
+}
+
+#[test]
+fn application_skip_binary() {
+    let app: Application = Application::build().with_command(&default_command).build();
+    let args = ["./app".to_string(), "--flag".to_string()];
+
+    let skipped: Vec<_> = app.skip_argv0(args.into_iter()).collect();
+    assert_eq!(skipped, ["--flag"]);
+    assert_eq!(app.render_help().lines().next(), Some(""));
+}
+
+#[test]
+fn application_use_argv0_name() {
+    let app: Application = Application::build()
+        .with_command(&default_command)
+        .with_use_argv0_name(true)
+        .build();
+    let args = ["/usr/bin/mytool".to_string(), "--help".to_string()];
+
+    let args: Vec<_> = app.skip_argv0(args.into_iter()).collect();
+    let Captured { result, out, .. } =
+        execute_captured(&app, &args.iter().map(String::as_str).collect::<Vec<_>>());
+    assert!(matches!(result, Ok(RunOutcome::HelpShown)));
+    assert!(out.starts_with("mytool\n"));
+    assert!(out.contains("Usage: mytool"));
+
+    // a configured name is kept
+    let app: Application = Application::build()
+        .with_name("app")
+        .with_command(&default_command)
+        .with_use_argv0_name(true)
+        .build();
+    let args = ["/usr/bin/mytool".to_string()];
+
+    assert_eq!(app.skip_argv0(args.into_iter()).count(), 0);
+    assert!(app.render_help().starts_with("app\n"));
+}
+
+fn record_pre_run() {
+    EVENTS.with(|events| events.borrow_mut().push("pre_run".to_string()));
+}
+
+fn record_command() {
+    EVENTS.with(|events| events.borrow_mut().push("command".to_string()));
+}
+
+#[test]
+fn application_pre_run() {
+    let subcommands = &[SubCommand::build()
+        .with_long_name("sub")
+        .with_command(&record_command)
+        .build()];
+
+    let app: Application = Application::build()
+        .with_subcommands(subcommands)
+        .with_command(&record_command)
+        .with_pre_run(record_pre_run)
+        .with_trace(record)
+        .build();
+
+    execute_captured(&app, &[]).result.unwrap();
+    assert_eq!(take_events(), ["pre_run", "command"]);
+
+    execute_captured(&app, &["sub"]).result.unwrap();
+    assert_eq!(
+        take_events(),
+        [r#"SubcommandDispatched("sub")"#, "pre_run", "command"]
+    );
+
+    execute_captured(&app, &["help"]).result.unwrap();
+    assert!(take_events().is_empty());
+}
+
+#[test]
+fn application_execute_into() {
+    static VERBOSE: FlagValue = FlagValue::new();
+    static FLAGS: &[Flag] = &[Flag::build()
+        .with_long_name("verbose")
+        .with_description("Prints more")
+        .with_deprecated(None)
+        .with_flag(&VERBOSE)
+        .build()];
+
+    let app: Application = Application::build()
+        .with_name("app")
+        .with_flags(FLAGS)
+        .with_command(&default_command)
+        .build();
+
+    let (mut out, mut err) = (Vec::new(), Vec::new());
+    assert_eq!(
+        app.execute_into(["--help"].iter(), &mut out, &mut err)
+            .unwrap(),
+        RunOutcome::HelpShown
+    );
+    assert_eq!(String::from_utf8(out).unwrap(), app.render_help());
+
+    let (mut out, mut err) = (Vec::new(), Vec::new());
+    assert_eq!(
+        app.execute_into(["--verbose"].iter(), &mut out, &mut err)
+            .unwrap(),
+        RunOutcome::Completed(())
+    );
+    assert!(VERBOSE.value());
+    assert!(out.is_empty());
+    assert!(String::from_utf8(err).unwrap().contains("--verbose"));
+}
+
+#[test]
+fn application_adversarial_arguments() {
+    let force = FlagValue::new();
+    let output = ParameterValue::new();
+    let extra = ParameterValue::new();
+
+    let flags = &[Flag::build()
+        .with_short_name("f")
+        .with_description("Forces")
+        .with_flag(&force)
+        .build()];
+    let parameters = &[
+        Parameter::build()
+            .with_short_name("o")
+            .with_description("Output")
+            .with_parameter(&output)
+            .build(),
+        Parameter::build()
+            .with_long_name("x")
+            .with_description("Extra")
+            .with_parameter(&extra)
+            .build(),
+    ];
+
+    for linear in [true, false] {
+        let app: Application = Application::build()
+            .with_flags(flags)
+            .with_parameters(parameters)
+            .with_command(&default_command)
+            .with_indexed_lookup(!linear)
+            .build();
+
+        let cases: &[(&str, CommandLineError)] = &[
+            ("-", CommandLineError::UnknownCommand("-".to_string())),
+            ("-é", CommandLineError::UnknownArgument("-é".to_string())),
+            (
+                "-fé",
+                CommandLineError::UnknownArgument("-é in -fé".to_string()),
+            ),
+            ("--é", CommandLineError::UnknownArgument("--é".to_string())),
+            ("+é", CommandLineError::UnknownCommand("+é".to_string())),
+            (
+                "--=x",
+                CommandLineError::UnknownArgument("--=x".to_string()),
+            ),
+            (
+                "--é=x",
+                CommandLineError::UnknownArgument("--é=x".to_string()),
+            ),
+        ];
+        for (arg, expected) in cases {
+            let result = execute_captured(&app, &[arg]).result;
+            assert_eq!(
+                format!("{:?}", result.err()),
+                format!("{:?}", Some(expected)),
+                "{}",
+                arg
+            );
+        }
+        assert!(!force.value());
+        assert_eq!(output.value(), None);
+
+        execute_captured(&app, &["--x="]).result.unwrap();
+        assert_eq!(extra.value(), Some(""));
+
+        // a lone `--` ends the options
+        execute_captured(&app, &["--"]).result.unwrap();
+        assert!(matches!(
+            execute_captured(&app, &["--", "--"]).result,
+            Err(CommandLineError::UnknownCommand(arg)) if arg == "--"
+        ));
+    }
+}
+
+#[test]
+fn application_introspection() {
+    static TEST_BOOL: FlagValue = FlagValue::new();
+    static QUIET: FlagValue = FlagValue::new();
+    static TEST_PARAM: ParameterValue = ParameterValue::new();
+    static FLAGS: &[Flag] = &[
+        Flag::build()
+            .with_short_name("t")
+            .with_long_name("test")
+            .with_flag(&TEST_BOOL)
+            .build(),
+        Flag::build().with_short_name("q").with_flag(&QUIET).build(),
+    ];
+    static PARAMETERS: &[Parameter] = &[Parameter::build()
+        .with_short_name("p")
+        .with_long_name("param")
+        .with_parameter(&TEST_PARAM)
+        .build()];
+    static SUBCOMMANDS: &[SubCommand] = &[SubCommand::build()
+        .with_long_name("headless")
+        .with_aliases(&["hl"])
+        .with_flags(FLAGS)
+        .with_command(&default_command)
+        .build()];
+    static APP: Application = Application::build()
+        .with_name("Example App")
+        .with_flags(FLAGS)
+        .with_parameters(PARAMETERS)
+        .with_subcommands(SUBCOMMANDS)
+        .with_command(&default_command)
+        .build();
+
+    assert_eq!(APP.subcommand_names(), ["headless"]);
+    assert_eq!(APP.flag_long_names(), ["test"]);
+    assert_eq!(APP.parameter_long_names(), ["param"]);
+
+    let headless = &APP.subcommands()[0];
+    assert!(headless.subcommand_names().is_empty());
+    assert_eq!(headless.flag_long_names(), ["test"]);
+    assert!(headless.parameter_long_names().is_empty());
+}
+
+#[test]
+fn application_execute_counting() {
+    let param = ParameterValue::new();
+    let params = &[Parameter::build()
+        .with_short_name("p")
+        .with_parameter(&param)
+        .build()];
+    let subcommands = &[SubCommand::build()
+        .with_long_name("sub")
+        .with_command(&default_command)
+        .build()];
+    let app: Application = Application::build()
+        .with_parameters(params)
+        .with_subcommands(subcommands)
+        .build();
+
+    let ((), consumed) = app.execute_counting(["-p", "val", "sub"].iter()).unwrap();
+    assert_eq!(consumed, 3);
+    assert_eq!(param.value(), Some("val"));
+
+    let ((), consumed) = app.execute_counting(["sub"].iter()).unwrap();
+    assert_eq!(consumed, 1);
+}
+
+#[test]
+fn application_parse_into_slots() {
+    let verbose = FlagValue::new();
+    let name = ParameterValue::new();
+    let flags = [Flag::build()
+        .with_long_name("verbose")
+        .with_short_name("v")
+        .with_flag(&verbose)
+        .build()];
+    let parameters = [Parameter::build()
+        .with_long_name("name")
+        .with_parameter(&name)
+        .build()];
+    let app = Application::build()
+        .with_flags(&flags)
+        .with_parameters(&parameters)
+        .with_command(&default_command)
+        .build();
+
+    let args = ["-v", "--name=ferris", "input", "--", "--verbose"];
+    let mut slots = [ArgSlot::default(); 6];
+    assert_eq!(app.parse_into_slots(&args, &mut slots).unwrap(), 4);
+    assert_eq!(
+        slots,
+        [
+            ArgSlot::Flag("verbose"),
+            ArgSlot::Param {
+                name: "name",
+                value: "ferris"
+            },
+            ArgSlot::Positional("input"),
+            ArgSlot::Positional("--verbose"),
+            ArgSlot::Empty,
+            ArgSlot::Empty,
+        ]
+    );
+    let ArgSlot::Param { value, .. } = slots[1] else {
+        unreachable!()
+    };
+    assert_eq!(value.as_ptr(), args[1]["--name=".len()..].as_ptr());
+    assert!(!verbose.value());
+
+    let mut slots = [ArgSlot::default(); 1];
+    assert!(matches!(
+        app.parse_into_slots(&["--name", "ferris", "input"], &mut slots),
+        Err(CommandLineError::ParseError(_))
+    ));
+    assert!(matches!(
+        app.parse_into_slots(&["--unknown"], &mut slots),
+        Err(CommandLineError::UnknownArgument(_))
+    ));
+    assert!(matches!(
+        app.parse_into_slots(&["--name"], &mut slots),
+        Err(CommandLineError::ExpectedValue { .. })
+    ));
+
+    // subcommands are not descended into
+    let subcommands = [SubCommand::build()
+        .with_long_name("sub")
+        .with_flags(&flags)
+        .with_command(&default_command)
+        .build()];
+    let app = Application::build().with_subcommands(&subcommands).build();
+    let mut slots = [ArgSlot::default(); 2];
+    assert_eq!(app.parse_into_slots(&["sub"], &mut slots).unwrap(), 1);
+    assert_eq!(slots[0], ArgSlot::Positional("sub"));
+    assert!(matches!(
+        app.parse_into_slots(&["sub", "--verbose"], &mut slots),
+        Err(CommandLineError::UnknownArgument(arg)) if arg == "--verbose"
+    ));
+}
@@ -0,0 +1,67 @@
+use super::*;
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll, Waker};
+
+/// A future that is pending on its first poll.
+struct YieldOnce(bool);
+
+impl Future for YieldOnce {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.0 {
+            Poll::Ready(())
+        } else {
+            self.0 = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = std::pin::pin!(future);
+    let mut cx = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
+fn async_command() -> Pin<Box<dyn Future<Output = u32>>> {
+    Box::pin(async {
+        YieldOnce(false).await;
+        42
+    })
+}
+
+fn sync_command() -> u32 {
+    7
+}
+
+static ASYNC_SUBCOMMANDS: &[SubCommand<u32>] = &[SubCommand::build()
+    .with_long_name("sync")
+    .with_command(&sync_command)
+    .build()];
+
+static ASYNC_APPLICATION: Application<u32> = Application::build()
+    .with_subcommands(ASYNC_SUBCOMMANDS)
+    .with_async_command(&async_command)
+    .build();
+
+#[test]
+fn async_command_awaited() {
+    let args: [&str; 0] = [];
+    let ret = block_on(ASYNC_APPLICATION.execute_async(args.iter()));
+    assert_eq!(ret.unwrap(), 42);
+}
+
+#[test]
+fn async_falls_back_to_sync_command() {
+    let args = &["sync"];
+    let ret = block_on(ASYNC_APPLICATION.execute_async(args.iter()));
+    assert_eq!(ret.unwrap(), 7);
+}
@@ -0,0 +1,181 @@
+use super::*;
+
+static VALUE: FlagValue = FlagValue::new();
+static PARAMETER: ParameterValue = ParameterValue::new();
+
+#[test]
+fn build_error_flag() {
+    assert_eq!(
+        Flag::build().with_long_name("flag").try_build().err(),
+        Some(BuildError::MissingValue)
+    );
+    assert_eq!(
+        Flag::build().with_flag(&VALUE).try_build().err(),
+        Some(BuildError::MissingName)
+    );
+    assert_eq!(
+        Flag::build()
+            .with_long_name("")
+            .with_flag(&VALUE)
+            .try_build()
+            .err(),
+        Some(BuildError::MissingName)
+    );
+
+    let flag = Flag::build()
+        .with_short_name("f")
+        .with_flag(&VALUE)
+        .try_build()
+        .unwrap();
+    assert_eq!(flag.short_name(), "f");
+}
+
+#[test]
+fn build_error_parameter() {
+    assert_eq!(
+        Parameter::build().with_long_name("param").try_build().err(),
+        Some(BuildError::MissingValue)
+    );
+    assert_eq!(
+        Parameter::build()
+            .with_parameter(&PARAMETER)
+            .try_build()
+            .err(),
+        Some(BuildError::MissingName)
+    );
+
+    let param = Parameter::build()
+        .with_long_name("param")
+        .with_parameter(&PARAMETER)
+        .try_build()
+        .unwrap();
+    assert_eq!(param.long_name(), "param");
+}
+
+#[test]
+fn build_error_subcommand() {
+    assert_eq!(
+        SubCommand::<()>::build()
+            .with_command(&default_command)
+            .try_build()
+            .err(),
+        Some(BuildError::MissingName)
+    );
+    assert_eq!(
+        SubCommand::<()>::build()
+            .with_long_name("sub")
+            .try_build()
+            .err(),
+        Some(BuildError::NoCommandOrSubcommand)
+    );
+
+    let flags = vec![
+        Flag::build()
+            .with_long_name("flag")
+            .with_flag(&VALUE)
+            .build(),
+        Flag::build()
+            .with_long_name("flag")
+            .with_flag(&VALUE)
+            .build(),
+    ];
+    assert_eq!(
+        SubCommand::build()
+            .with_long_name("sub")
+            .with_owned_flags(flags)
+            .with_command(&default_command)
+            .try_build()
+            .err(),
+        Some(BuildError::DuplicateName("--flag".to_string()))
+    );
+}
+
+#[test]
+fn build_error_application() {
+    assert_eq!(
+        Application::<()>::build().try_build().err(),
+        Some(BuildError::NoCommandOrSubcommand)
+    );
+
+    // a flag and a parameter share one namespace
+    let flags = vec![Flag::build().with_short_name("v").with_flag(&VALUE).build()];
+    let params = vec![Parameter::build()
+        .with_short_name("v")
+        .with_parameter(&PARAMETER)
+        .build()];
+    assert_eq!(
+        Application::build()
+            .with_owned_flags(flags)
+            .with_owned_parameters(params)
+            .with_command(&default_command)
+            .try_build()
+            .err(),
+        Some(BuildError::DuplicateName("-v".to_string()))
+    );
+
+    // an alias clashing with another long name
+    let flags = vec![Flag::build()
+        .with_long_name("color")
+        .with_flag(&VALUE)
+        .build()];
+    let params = vec![Parameter::build()
+        .with_long_name("paint")
+        .with_aliases(&["color"])
+        .with_parameter(&PARAMETER)
+        .build()];
+    assert_eq!(
+        Application::build()
+            .with_owned_flags(flags)
+            .with_owned_parameters(params)
+            .with_command(&default_command)
+            .try_build()
+            .err(),
+        Some(BuildError::DuplicateName("--color".to_string()))
+    );
+
+    // an alias clashing with another subcommand
+    let subcommands = vec![
+        SubCommand::build()
+            .with_long_name("remove")
+            .with_aliases(&["rm"])
+            .with_command(&default_command)
+            .build(),
+        SubCommand::build()
+            .with_long_name("rm")
+            .with_command(&default_command)
+            .build(),
+    ];
+    let result = Application::build()
+        .with_owned_subcommands(subcommands)
+        .try_build();
+    assert_eq!(
+        result.as_ref().err(),
+        Some(&BuildError::DuplicateName("rm".to_string()))
+    );
+    assert_eq!(result.err().unwrap().to_string(), "Duplicate name: rm");
+
+    let app = Application::build()
+        .with_owned_flags(vec![Flag::build()
+            .with_short_name("v")
+            .with_long_name("v")
+            .with_flag(&VALUE)
+            .build()])
+        .with_command(&default_command)
+        .try_build()
+        .unwrap();
+    assert_eq!(app.flags().len(), 1);
+}
+
+#[test]
+#[should_panic(expected = "must be built with build_owned")]
+fn build_rejects_owned_lists() {
+    static VALUE: FlagValue = FlagValue::new();
+
+    let _: Application = Application::build()
+        .with_owned_flags(vec![Flag::build()
+            .with_long_name("verbose")
+            .with_flag(&VALUE)
+            .build()])
+        .with_command(&default_command)
+        .build();
+}
@@ -0,0 +1,131 @@
+use super::*;
+
+static VERBOSE: FlagValue = FlagValue::new();
+static CONFIG: ParameterValue = ParameterValue::new();
+static OUTPUT: ParameterValue = ParameterValue::new();
+static HOST: ParameterValue = ParameterValue::new();
+static COLOR: ParameterValue = ParameterValue::new();
+
+static FLAGS: &[Flag] = &[Flag::build()
+    .with_long_name("verbose")
+    .with_short_name("v")
+    .with_description("Prints more output")
+    .with_flag(&VERBOSE)
+    .build()];
+
+static PARAMETERS: &[Parameter] = &[
+    Parameter::build()
+        .with_long_name("config")
+        .with_short_name("c")
+        .with_description("The config file")
+        .with_value_hint(ValueHint::FilePath)
+        .with_parameter(&CONFIG)
+        .build(),
+    Parameter::build()
+        .with_long_name("output")
+        .with_description("The output directory")
+        .with_value_hint(ValueHint::DirPath)
+        .with_parameter(&OUTPUT)
+        .build(),
+    Parameter::build()
+        .with_long_name("host")
+        .with_description("The host to deploy to")
+        .with_value_hint(ValueHint::Hostname)
+        .with_parameter(&HOST)
+        .build(),
+    Parameter::build()
+        .with_long_name("color")
+        .with_description("When to color the output")
+        .with_allowed_values(&["auto", "always", "never"])
+        .with_parameter(&COLOR)
+        .build(),
+];
+
+static SUBCOMMANDS: &[SubCommand] = &[
+    SubCommand::build()
+        .with_long_name("deploy")
+        .with_description("Deploys the app")
+        .with_command(&default_command)
+        .build(),
+    SubCommand::build()
+        .with_long_name("status")
+        .with_description("Shows the status")
+        .with_command(&default_command)
+        .build(),
+];
+
+static APP: Application = Application::build()
+    .with_name("app")
+    .with_flags(FLAGS)
+    .with_parameters(PARAMETERS)
+    .with_subcommands(SUBCOMMANDS)
+    .build();
+
+#[test]
+fn completion_zsh_value_hints() {
+    let script = APP.render_completion(Shell::Zsh);
+
+    assert!(script.starts_with("#compdef app\n"));
+    assert!(script.contains("'--verbose[Prints more output]'"));
+    assert!(script.contains("'-v[Prints more output]'"));
+    assert!(script.contains("'--config=[The config file]:VALUE:_files'"));
+    assert!(script.contains("'-c+[The config file]:VALUE:_files'"));
+    assert!(script.contains("'--output=[The output directory]:VALUE:_directories'"));
+    assert!(script.contains("'--host=[The host to deploy to]:VALUE:_hosts'"));
+    assert!(script.contains("'--color=[When to color the output]:VALUE:(auto always never)'"));
+    assert!(script.contains("'1:subcommand:(deploy status)'"));
+    assert!(script.ends_with("_app \"$@\"\n"));
+}
+
+#[test]
+fn completion_bash() {
+    let script = APP.render_completion(Shell::Bash);
+
+    assert!(script
+        .contains("        --config|-c)\n            COMPREPLY=($(compgen -f -- \"$cur\"))\n"));
+    assert!(
+        script.contains("        --output)\n            COMPREPLY=($(compgen -d -- \"$cur\"))\n")
+    );
+    assert!(script
+        .contains("        --host)\n            COMPREPLY=($(compgen -A hostname -- \"$cur\"))\n"));
+    assert!(script.contains("compgen -W 'auto always never'"));
+    assert!(script
+        .contains("compgen -W '--verbose -v --config -c --output --host --color deploy status'"));
+    assert!(script.ends_with("complete -F _app app\n"));
+}
+
+#[test]
+fn completion_fish() {
+    let script = APP.render_completion(Shell::Fish);
+
+    assert!(script.contains("complete -c app -s v -l verbose -d 'Prints more output'\n"));
+    assert!(script.contains("complete -c app -s c -l config -r -F -d 'The config file'\n"));
+    assert!(script.contains("complete -c app -l output -r -f -a '(__fish_complete_directories)'"));
+    assert!(script.contains("complete -c app -l host -r -f -a '(__fish_print_hostnames)'"));
+    assert!(script.contains("complete -c app -l color -r -f -a 'auto always never'"));
+    assert!(script
+        .contains("complete -c app -n __fish_use_subcommand -f -a deploy -d 'Deploys the app'\n"));
+}
+
+#[test]
+fn completion_escapes_descriptions() {
+    let flags = &[Flag::build()
+        .with_long_name("force")
+        .with_description("Don't ask [dangerous]")
+        .with_flag(&VERBOSE)
+        .build()];
+
+    let app: Application = Application::build()
+        .with_name("my-app")
+        .with_flags(flags)
+        .with_command(&default_command)
+        .build();
+
+    let zsh = app.render_completion(Shell::Zsh);
+    assert!(zsh.contains(r"'--force[Don'\''t ask \[dangerous\]]'"));
+    assert!(zsh.contains("_my_app() {"));
+    assert!(zsh.contains("#compdef my-app\n"));
+
+    let fish = app.render_completion(Shell::Fish);
+    assert!(fish.contains(r"-d 'Don\'t ask [dangerous]'"));
+}
@@ -0,0 +1,241 @@
+use super::*;
+use crate::constexpr::report::write_error;
+
+#[test]
+fn error_kind() {
+    let errors = [
+        (
+            CommandLineError::UnknownArgument("--unknown".to_string()),
+            "unknown_argument",
+        ),
+        (
+            CommandLineError::UnexpectedParameter("value".to_string()),
+            "unexpected_parameter",
+        ),
+        (
+            CommandLineError::UnknownCommand("unknown".to_string()),
+            "unknown_command",
+        ),
+        (
+            CommandLineError::ExpectedValue {
+                arg: "--param".to_string(),
+                names: "-p, --param".to_string(),
+                value_name: "VALUE".to_string(),
+            },
+            "expected_value",
+        ),
+        (CommandLineError::ExpectedSubcommand, "expected_subcommand"),
+        (
+            CommandLineError::ValidationFailed {
+                name: "--param".to_string(),
+                message: "invalid".to_string(),
+            },
+            "validation_failed",
+        ),
+        (
+            CommandLineError::MissingDependency {
+                flag: "--flag".to_string(),
+                requires: "--param".to_string(),
+            },
+            "missing_dependency",
+        ),
+        (
+            CommandLineError::ParseError("Trailing backslash".to_string()),
+            "parse_error",
+        ),
+        (
+            CommandLineError::FlagTakesNoValue("--flag=1".to_string()),
+            "flag_takes_no_value",
+        ),
+        (CommandLineError::MaxDepthExceeded, "max_depth_exceeded"),
+        (
+            CommandLineError::MissingRequiredParameter("--output".to_string()),
+            "missing_required_parameter",
+        ),
+        (
+            CommandLineError::MissingOneOf(vec!["--stdin".to_string(), "--file".to_string()]),
+            "missing_one_of",
+        ),
+        (
+            CommandLineError::ConflictingFlags(vec!["--stdin".to_string(), "--file".to_string()]),
+            "conflicting_flags",
+        ),
+        (
+            CommandLineError::InvalidValue {
+                name: "-D".to_string(),
+                value: "noeq".to_string(),
+                expected: "KEY=VALUE".to_string(),
+            },
+            "invalid_value",
+        ),
+        (
+            CommandLineError::MissingEquals {
+                arg: "--output".to_string(),
+                suggestion: "--output=file".to_string(),
+            },
+            "missing_equals",
+        ),
+    ];
+
+    for (error, kind) in &errors {
+        assert_eq!(error.kind(), *kind, "{}", error);
+    }
+}
+
+#[test]
+fn error_kind_from_parse() {
+    let app: Application = Application::build().with_command(&default_command).build();
+
+    let captured = execute_captured(&app, &["--unknown"]);
+    assert_eq!(captured.result.unwrap_err().kind(), "unknown_argument");
+}
+
+#[test]
+fn error_boxed() {
+    fn run(app: &Application, args: &[&str]) -> Result<(), Box<dyn std::error::Error>> {
+        execute_captured(app, args).result?;
+        Ok(())
+    }
+
+    let app: Application = Application::build().with_command(&default_command).build();
+
+    assert!(run(&app, &[]).is_ok());
+
+    let error = run(&app, &["--unknown"]).unwrap_err();
+    assert_eq!(error.to_string(), "Unknown argument: --unknown");
+    assert!(error.source().is_none());
+    assert!(error.downcast_ref::<CommandLineError>().is_some());
+}
+
+#[test]
+fn error_colored_output() {
+    let error = CommandLineError::UnknownArgument("--unknown".to_string());
+
+    let mut plain = Vec::new();
+    write_error(&mut plain, &error, None, false).unwrap();
+    assert_eq!(
+        String::from_utf8(plain).unwrap(),
+        "error: Unknown argument: --unknown\n"
+    );
+
+    let mut colored = Vec::new();
+    write_error(&mut colored, &error, None, true).unwrap();
+    assert_eq!(
+        String::from_utf8(colored).unwrap(),
+        "\x1b[1;31merror:\x1b[0m Unknown argument: \x1b[1m--unknown\x1b[0m\n"
+    );
+
+    let mut unnamed = Vec::new();
+    write_error(
+        &mut unnamed,
+        &CommandLineError::ExpectedSubcommand,
+        None,
+        true,
+    )
+    .unwrap();
+    assert_eq!(
+        String::from_utf8(unnamed).unwrap(),
+        "\x1b[1;31merror:\x1b[0m Expected subcommand\n"
+    );
+}
+
+#[test]
+fn error_color_choice() {
+    assert!(ColorChoice::Auto.enabled(true, false));
+    assert!(!ColorChoice::Auto.enabled(false, false));
+    assert!(!ColorChoice::Auto.enabled(true, true));
+    assert!(ColorChoice::Always.enabled(false, true));
+    assert!(!ColorChoice::Never.enabled(true, false));
+}
+
+#[test]
+fn error_exit_on_error_disabled() {
+    use std::io::Write;
+    use std::sync::Mutex;
+
+    static WRITTEN: Mutex<Vec<u8>> = Mutex::new(Vec::new());
+
+    struct Recorder;
+
+    impl Write for Recorder {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            WRITTEN.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn recorder() -> Box<dyn Write> {
+        Box::new(Recorder)
+    }
+
+    let app: Application = Application::build()
+        .with_command(&default_command)
+        .with_exit_on_error(false)
+        .with_error_writer(recorder)
+        .with_color(ColorChoice::Always)
+        .build();
+
+    let error = app.execute(["--unknown"].iter()).unwrap_err();
+    assert_eq!(error.kind(), "unknown_argument");
+    assert_eq!(error.exit_code(), 2);
+    assert!(WRITTEN.lock().unwrap().is_empty());
+
+    app.print_error(&error);
+    assert_eq!(
+        String::from_utf8(WRITTEN.lock().unwrap().clone()).unwrap(),
+        "\x1b[1;31merror:\x1b[0m Unknown argument: \x1b[1m--unknown\x1b[0m\n"
+    );
+}
+
+#[test]
+fn error_formatter() {
+    use std::io::Write;
+    use std::sync::Mutex;
+
+    static WRITTEN: Mutex<Vec<u8>> = Mutex::new(Vec::new());
+
+    struct Recorder;
+
+    impl Write for Recorder {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            WRITTEN.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn recorder() -> Box<dyn Write> {
+        Box::new(Recorder)
+    }
+
+    fn french(error: &CommandLineError) -> String {
+        match error {
+            CommandLineError::UnknownArgument(arg) => format!("Argument inconnu : {}", arg),
+            error => error.to_string(),
+        }
+    }
+
+    fn print(formatter: Option<fn(&CommandLineError) -> String>) -> String {
+        let builder = Application::<()>::build()
+            .with_command(&default_command)
+            .with_error_writer(recorder)
+            .with_color(ColorChoice::Never);
+        let app = match formatter {
+            Some(formatter) => builder.with_error_formatter(formatter).build(),
+            None => builder.build(),
+        };
+        let error = app.execute(["--inconnu"].iter()).unwrap_err();
+        app.print_error(&error);
+        String::from_utf8(std::mem::take(&mut *WRITTEN.lock().unwrap())).unwrap()
+    }
+
+    assert_eq!(print(None), "error: Unknown argument: --inconnu\n");
+    assert_eq!(print(Some(french)), "error: Argument inconnu : --inconnu\n");
+}
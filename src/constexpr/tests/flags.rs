@@ -127,3 +127,1174 @@ fn flag_parsing_4() {
 
     assert!(f.value() && g.value());
 }
+
+#[test]
+fn flag_parsing_5() {
+    let f: FlagValue = FlagValue::new();
+    let g: FlagValue = FlagValue::new();
+
+    let flags: &[Flag] = &[
+        Flag::build()
+            .with_short_name("f")
+            .with_long_name("flag")
+            .with_description("A flag")
+            .with_flag(&f)
+            .build(),
+        Flag::build()
+            .with_short_name("g")
+            .with_long_name("gflag")
+            .with_description("A gflag")
+            .with_flag(&g)
+            .build(),
+    ];
+
+    let app: Application = Application::build()
+        .with_flags(flags)
+        .with_command(&default_command)
+        .build();
+
+    let args: [&str; 1] = ["-fg"];
+
+    app.execute(args.iter()).unwrap();
+
+    assert!(f.value() && g.value());
+}
+
+#[test]
+fn flag_cluster_unknown() {
+    let f: FlagValue = FlagValue::new();
+    let g: FlagValue = FlagValue::new();
+
+    let flags: &[Flag] = &[
+        Flag::build()
+            .with_short_name("f")
+            .with_long_name("flag")
+            .with_description("A flag")
+            .with_flag(&f)
+            .build(),
+        Flag::build()
+            .with_short_name("g")
+            .with_long_name("gflag")
+            .with_description("A gflag")
+            .with_flag(&g)
+            .build(),
+    ];
+
+    let app: Application = Application::build()
+        .with_flags(flags)
+        .with_command(&default_command)
+        .build();
+
+    let args: [&str; 1] = ["-fxg"];
+
+    match app.execute(args.iter()) {
+        Err(err @ CommandLineError::UnknownArgument(_)) => {
+            assert_eq!(err.to_string(), "Unknown argument: -x in -fxg");
+        }
+        _ => panic!("expected unknown argument"),
+    }
+
+    assert!(!g.value());
+}
+
+#[test]
+fn find_by_name_unique() {
+    let f: FlagValue = FlagValue::new();
+    let g: FlagValue = FlagValue::new();
+
+    let flags: &[Flag] = &[
+        Flag::build()
+            .with_short_name("f")
+            .with_long_name("flag")
+            .with_flag(&f)
+            .build(),
+        Flag::build()
+            .with_short_name("g")
+            .with_long_name("gflag")
+            .with_flag(&g)
+            .build(),
+    ];
+
+    assert!(std::ptr::eq(
+        flags.find_by_short_name("f").unwrap(),
+        &flags[0]
+    ));
+    assert!(std::ptr::eq(
+        flags.find_by_long_name("flag").unwrap(),
+        &flags[0]
+    ));
+    assert!(std::ptr::eq(
+        flags.find_by_short_name("g").unwrap(),
+        &flags[1]
+    ));
+    assert!(std::ptr::eq(
+        flags.find_by_long_name("gflag").unwrap(),
+        &flags[1]
+    ));
+    assert!(flags.find_by_short_name("x").is_none());
+    assert!(flags.find_by_long_name("fla").is_none());
+}
+
+#[test]
+fn find_by_name_duplicate() {
+    let f: FlagValue = FlagValue::new();
+    let g: FlagValue = FlagValue::new();
+
+    // misconfigured: both flags share the same names, the first registered always wins
+    let flags: &[Flag] = &[
+        Flag::build()
+            .with_short_name("f")
+            .with_long_name("flag")
+            .with_flag(&f)
+            .build(),
+        Flag::build()
+            .with_short_name("f")
+            .with_long_name("flag")
+            .with_flag(&g)
+            .build(),
+    ];
+
+    assert!(std::ptr::eq(
+        flags.find_by_short_name("f").unwrap(),
+        &flags[0]
+    ));
+    assert!(std::ptr::eq(
+        flags.find_by_long_name("flag").unwrap(),
+        &flags[0]
+    ));
+
+    let app: Application = Application::build()
+        .with_flags(flags)
+        .with_command(&default_command)
+        .build();
+
+    let args: [&str; 2] = ["-f", "--flag"];
+
+    app.execute(args.iter()).unwrap();
+
+    assert!(f.value() && !g.value());
+}
+
+#[test]
+fn flag_deprecated() {
+    let f: FlagValue = FlagValue::new();
+    let g: FlagValue = FlagValue::new();
+
+    let flags: &[Flag] = &[
+        Flag::build()
+            .with_short_name("f")
+            .with_long_name("flag")
+            .with_description("A flag")
+            .with_deprecated(Some("use --gflag instead"))
+            .with_flag(&f)
+            .build(),
+        Flag::build()
+            .with_short_name("g")
+            .with_long_name("gflag")
+            .with_description("A gflag")
+            .with_deprecated(None)
+            .with_flag(&g)
+            .build(),
+    ];
+
+    let app: Application = Application::build()
+        .with_flags(flags)
+        .with_command(&default_command)
+        .build();
+
+    let Captured { result, err, .. } = execute_captured(&app, &["-f", "--gflag"]);
+
+    assert!(matches!(result, Ok(RunOutcome::Completed(()))));
+    assert!(f.value() && g.value());
+    assert_eq!(
+        err,
+        "warning: --flag is deprecated: use --gflag instead\nwarning: --gflag is deprecated\n"
+    );
+
+    let help = capture_help(&app, &app);
+    assert!(help.contains("  -f, --flag        A flag [deprecated]\n"));
+}
+
+#[test]
+fn flag_not_deprecated() {
+    let f: FlagValue = FlagValue::new();
+
+    let flags: &[Flag] = &[Flag::build()
+        .with_short_name("f")
+        .with_long_name("flag")
+        .with_description("A flag")
+        .with_flag(&f)
+        .build()];
+
+    let app: Application = Application::build()
+        .with_flags(flags)
+        .with_command(&default_command)
+        .build();
+
+    let Captured { err, .. } = execute_captured(&app, &["-f"]);
+
+    assert!(f.value());
+    assert!(err.is_empty());
+}
+
+#[test]
+fn flag_requires() {
+    fn run(args: &[&str]) -> Result<RunOutcome<()>, CommandLineError> {
+        let sign = FlagValue::new();
+        let key = ParameterValue::new();
+
+        let flags = &[Flag::build()
+            .with_long_name("sign")
+            .with_description("Signs the output")
+            .with_requires(&["key"])
+            .with_flag(&sign)
+            .build()];
+
+        let parameters = &[Parameter::build()
+            .with_long_name("key")
+            .with_description("The signing key")
+            .with_parameter(&key)
+            .build()];
+
+        let app: Application = Application::build()
+            .with_flags(flags)
+            .with_parameters(parameters)
+            .with_command(&default_command)
+            .build();
+
+        execute_captured(&app, args).result
+    }
+
+    assert!(matches!(
+        run(&["--sign"]),
+        Err(CommandLineError::MissingDependency { flag, requires })
+            if flag == "--sign" && requires == "--key"
+    ));
+    assert!(matches!(
+        run(&["--sign", "--key", "x"]),
+        Ok(RunOutcome::Completed(()))
+    ));
+    assert!(matches!(
+        run(&["--key", "x"]),
+        Ok(RunOutcome::Completed(()))
+    ));
+    assert!(matches!(run(&[]), Ok(RunOutcome::Completed(()))));
+}
+
+#[test]
+fn flag_plus_minus() {
+    let debug = FlagValue::new();
+
+    let flags = &[Flag::build()
+        .with_short_name("d")
+        .with_long_name("debug")
+        .with_description("Enables debugging")
+        .with_flag(&debug)
+        .build()];
+
+    let app: Application = Application::build()
+        .with_flags(flags)
+        .with_command(&default_command)
+        .with_plus_minus_flags(true)
+        .build();
+
+    execute_captured(&app, &["+debug"]).result.unwrap();
+    assert!(debug.value());
+
+    execute_captured(&app, &["-debug"]).result.unwrap();
+    assert!(!debug.value());
+
+    execute_captured(&app, &["-debug", "--debug"])
+        .result
+        .unwrap();
+    assert!(debug.value());
+
+    assert!(matches!(
+        execute_captured(&app, &["+unknown"]).result,
+        Err(CommandLineError::UnknownArgument(arg)) if arg == "+unknown"
+    ));
+}
+
+#[test]
+fn flag_plus_minus_disabled() {
+    let debug = FlagValue::new();
+
+    let flags = &[Flag::build()
+        .with_long_name("debug")
+        .with_description("Enables debugging")
+        .with_flag(&debug)
+        .build()];
+
+    let app: Application = Application::build()
+        .with_flags(flags)
+        .with_command(&default_command)
+        .build();
+
+    assert!(matches!(
+        execute_captured(&app, &["+debug"]).result,
+        Err(CommandLineError::UnknownCommand(_))
+    ));
+    assert!(matches!(
+        execute_captured(&app, &["-debug"]).result,
+        Err(CommandLineError::UnknownArgument(_))
+    ));
+}
+
+#[test]
+fn flag_ignore_unknown() {
+    let f = FlagValue::new();
+    let p = ParameterValue::new();
+
+    let flags = &[Flag::build()
+        .with_short_name("f")
+        .with_long_name("flag")
+        .with_description("A flag")
+        .with_flag(&f)
+        .build()];
+
+    let parameters = &[Parameter::build()
+        .with_long_name("param")
+        .with_description("A parameter")
+        .with_parameter(&p)
+        .build()];
+
+    let app: Application = Application::build()
+        .with_flags(flags)
+        .with_parameters(parameters)
+        .with_command(&default_command)
+        .with_ignore_unknown(true)
+        .build();
+
+    let result = execute_captured(
+        &app,
+        &["--future", "-f", "-x", "--param", "value", "--later=1"],
+    )
+    .result;
+
+    assert!(matches!(result, Ok(RunOutcome::Completed(()))));
+    assert!(f.value());
+    assert_eq!(p.value(), Some("value"));
+    assert_eq!(app.unknown_args(), &["--future", "-x", "--later=1"]);
+}
+
+#[test]
+fn flag_unknown_not_ignored() {
+    let app: Application = Application::build().with_command(&default_command).build();
+
+    assert!(matches!(
+        execute_captured(&app, &["--future"]).result,
+        Err(CommandLineError::UnknownArgument(_))
+    ));
+    assert!(app.unknown_args().is_empty());
+}
+
+#[test]
+fn flag_indexed_lookup() {
+    const COUNT: usize = 500;
+
+    let values: &[FlagValue] = Vec::from_iter((0..COUNT).map(|_| FlagValue::new())).leak();
+    let flags: &[Flag] = Vec::from_iter((0..COUNT).map(|i| {
+        Flag::build()
+            .with_short_name(format!("s{}", i).leak())
+            .with_long_name(format!("flag{}", i).leak())
+            .with_description("A generated flag")
+            .with_flag(&values[i])
+            .build()
+    }))
+    .leak();
+
+    let app: Application = Application::build()
+        .with_flags(flags)
+        .with_command(&default_command)
+        .with_indexed_lookup(true)
+        .build();
+
+    let result = execute_captured(&app, &["--flag0", "-s250", "--flag499"]).result;
+
+    assert!(matches!(result, Ok(RunOutcome::Completed(()))));
+    for (i, value) in values.iter().enumerate() {
+        assert_eq!(value.value(), matches!(i, 0 | 250 | 499), "flag{}", i);
+    }
+
+    assert!(matches!(
+        execute_captured(&app, &["--flag500"]).result,
+        Err(CommandLineError::UnknownArgument(_))
+    ));
+}
+
+#[test]
+fn flag_indexed_lookup_duplicate() {
+    let f = FlagValue::new();
+    let g = FlagValue::new();
+
+    let flags = &[
+        Flag::build()
+            .with_short_name("f")
+            .with_long_name("flag")
+            .with_description("A flag")
+            .with_flag(&f)
+            .build(),
+        Flag::build()
+            .with_short_name("f")
+            .with_long_name("flag")
+            .with_description("A duplicate flag")
+            .with_flag(&g)
+            .build(),
+    ];
+
+    let subcommand_flag = FlagValue::new();
+    let subcommand_flags = &[Flag::build()
+        .with_long_name("nested")
+        .with_description("A subcommand flag")
+        .with_flag(&subcommand_flag)
+        .build()];
+
+    let subcommands = &[SubCommand::build()
+        .with_long_name("sub")
+        .with_flags(subcommand_flags)
+        .with_command(&default_command)
+        .build()];
+
+    let app: Application = Application::build()
+        .with_flags(flags)
+        .with_subcommands(subcommands)
+        .with_command(&default_command)
+        .with_indexed_lookup(true)
+        .build();
+
+    execute_captured(&app, &["--flag", "-f", "sub", "--nested"])
+        .result
+        .unwrap();
+
+    assert!(f.value() && !g.value());
+    assert!(subcommand_flag.value());
+}
+
+#[test]
+fn flag_unknown_handler() {
+    fn handler(arg: &str) -> UnknownDisposition {
+        match arg {
+            "--skip" => UnknownDisposition::Ignore,
+            "--keep" | "-k" | "--keep=1" => UnknownDisposition::Positional,
+            _ => UnknownDisposition::Error,
+        }
+    }
+
+    let positionals = PositionalValue::new();
+
+    let app: Application = Application::build()
+        .with_positionals(&positionals)
+        .with_command(&default_command)
+        .with_unknown_handler(handler)
+        .build();
+
+    let result = execute_captured(&app, &["--skip", "--keep", "a", "-k", "--keep=1"]).result;
+    assert!(matches!(result, Ok(RunOutcome::Completed(()))));
+    assert_eq!(positionals.values(), &["--keep", "a", "-k", "--keep=1"]);
+
+    assert!(matches!(
+        execute_captured(&app, &["--other"]).result,
+        Err(CommandLineError::UnknownArgument(arg)) if arg == "--other"
+    ));
+    assert!(matches!(
+        execute_captured(&app, &["--other=1"]).result,
+        Err(CommandLineError::UnknownArgument(arg)) if arg == "--other=1"
+    ));
+}
+
+#[test]
+fn flag_unknown_handler_without_positionals() {
+    let app: Application = Application::build()
+        .with_command(&default_command)
+        .with_unknown_handler(|_| UnknownDisposition::Positional)
+        .build();
+
+    assert!(matches!(
+        execute_captured(&app, &["--keep"]).result,
+        Err(CommandLineError::UnknownArgument(_))
+    ));
+}
+
+#[test]
+fn flag_unknown_handler_error_collected() {
+    let app: Application = Application::build()
+        .with_command(&default_command)
+        .with_unknown_handler(|_| UnknownDisposition::Error)
+        .with_ignore_unknown(true)
+        .build();
+
+    execute_captured(&app, &["--future"]).result.unwrap();
+    assert_eq!(app.unknown_args(), &["--future"]);
+}
+
+#[test]
+fn flag_cluster_transactional() {
+    let a = FlagValue::new();
+    let b = FlagValue::new();
+    let p = ParameterValue::new();
+
+    let flags = &[
+        Flag::build()
+            .with_short_name("a")
+            .with_description("An a flag")
+            .with_flag(&a)
+            .build(),
+        Flag::build()
+            .with_short_name("b")
+            .with_description("A b flag")
+            .with_flag(&b)
+            .build(),
+    ];
+
+    let parameters = &[Parameter::build()
+        .with_short_name("p")
+        .with_description("A number")
+        .with_validator(|value| match value.parse::<u32>() {
+            Ok(_) => Ok(()),
+            Err(_) => Err("expected a number".to_string()),
+        })
+        .with_parameter(&p)
+        .build()];
+
+    let app: Application = Application::build()
+        .with_flags(flags)
+        .with_parameters(parameters)
+        .with_command(&default_command)
+        .build();
+
+    assert!(matches!(
+        execute_captured(&app, &["-abc"]).result,
+        Err(CommandLineError::UnknownArgument(arg)) if arg == "-c in -abc"
+    ));
+    assert!(!a.value() && !b.value());
+
+    assert!(matches!(
+        execute_captured(&app, &["-abp"]).result,
+        Err(CommandLineError::ExpectedValue { .. })
+    ));
+    assert!(!a.value() && !b.value());
+
+    assert!(matches!(
+        execute_captured(&app, &["-abp", "x"]).result,
+        Err(CommandLineError::ValidationFailed { .. })
+    ));
+    assert!(!a.value() && !b.value());
+
+    execute_captured(&app, &["-abp", "5"]).result.unwrap();
+    assert!(a.value() && b.value());
+    assert_eq!(p.value(), Some("5"));
+}
+
+#[test]
+fn flag_takes_no_value() {
+    let t = FlagValue::new();
+
+    let flags = &[Flag::build()
+        .with_long_name("test")
+        .with_description("A test flag")
+        .with_flag(&t)
+        .build()];
+
+    let app: Application = Application::build()
+        .with_flags(flags)
+        .with_command(&default_command)
+        .build();
+
+    let result = execute_captured(&app, &["--test=1"]).result;
+    assert!(matches!(
+        &result,
+        Err(CommandLineError::FlagTakesNoValue(arg)) if arg == "--test=1"
+    ));
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "Flag takes no value: --test=1"
+    );
+    assert!(!t.value());
+}
+
+#[test]
+fn flag_unrecognized_long_arguments() {
+    let t = FlagValue::new();
+
+    let flags = &[Flag::build()
+        .with_long_name("test")
+        .with_description("A test flag")
+        .with_flag(&t)
+        .build()];
+
+    let app: Application = Application::build()
+        .with_flags(flags)
+        .with_command(&default_command)
+        .build();
+
+    // an unrecognized name is unknown, with or without a value
+    assert!(matches!(
+        execute_captured(&app, &["--unknown"]).result,
+        Err(CommandLineError::UnknownArgument(arg)) if arg == "--unknown"
+    ));
+    assert!(matches!(
+        execute_captured(&app, &["--unknown=x"]).result,
+        Err(CommandLineError::UnknownArgument(arg)) if arg == "--unknown=x"
+    ));
+
+    // a value given to a name that exists but takes none
+    assert!(matches!(
+        execute_captured(&app, &["--test=x"]).result,
+        Err(CommandLineError::FlagTakesNoValue(arg)) if arg == "--test=x"
+    ));
+    assert!(matches!(
+        execute_captured(&app, &["--help=x"]).result,
+        Err(CommandLineError::UnexpectedParameter(arg)) if arg == "--help=x"
+    ));
+
+    // without the built-in help flag, help is just another unknown name
+    let app: Application = Application::build()
+        .with_flags(flags)
+        .with_command(&default_command)
+        .with_help_flag(false)
+        .build();
+    assert!(matches!(
+        execute_captured(&app, &["--help=x"]).result,
+        Err(CommandLineError::UnknownArgument(arg)) if arg == "--help=x"
+    ));
+    assert!(!t.value());
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "Flags --first and --second share the same FlagValue.")]
+fn flag_shared_value() {
+    let f = FlagValue::new();
+
+    let flags = &[
+        Flag::build()
+            .with_long_name("first")
+            .with_description("A flag")
+            .with_flag(&f)
+            .build(),
+        Flag::build()
+            .with_long_name("second")
+            .with_description("Another flag")
+            .with_flag(&f)
+            .build(),
+    ];
+
+    let app: Application = Application::build()
+        .with_flags(flags)
+        .with_command(&default_command)
+        .build();
+
+    let _ = execute_captured(&app, &[]);
+}
+
+#[test]
+fn flag_mode() {
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    enum Mode {
+        Debug,
+        Release,
+        Test,
+    }
+
+    static MODE: ModeValue<Mode> = ModeValue::new();
+    static FLAGS: &[Flag] = &[
+        Flag::build()
+            .with_long_name("debug")
+            .with_description("Builds for debugging")
+            .with_mode_flag(&MODE, &Mode::Debug)
+            .build(),
+        Flag::build()
+            .with_long_name("release")
+            .with_description("Builds for release")
+            .with_mode_flag(&MODE, &Mode::Release)
+            .build(),
+        Flag::build()
+            .with_long_name("test")
+            .with_description("Builds for testing")
+            .with_mode_flag(&MODE, &Mode::Test)
+            .build(),
+    ];
+
+    let app: Application = Application::build()
+        .with_flags(FLAGS)
+        .with_command(&default_command)
+        .build();
+
+    execute_captured(&app, &[]).result.unwrap();
+    assert_eq!(MODE.value(), None);
+
+    execute_captured(&app, &["--debug", "--release"])
+        .result
+        .unwrap();
+    assert_eq!(MODE.value(), Some(Mode::Release));
+
+    let values = app.collect_values();
+    assert_eq!(values["debug"], ArgValue::Flag(false));
+    assert_eq!(values["release"], ArgValue::Flag(true));
+}
+
+/// Executes `args` against a command requiring exactly one of `--stdin`, `--file` or `--url`.
+fn execute_one_of(args: &[&str]) -> Result<RunOutcome<()>, CommandLineError> {
+    let stdin = FlagValue::new();
+    let file = ParameterValue::new();
+    let url = ParameterValue::new();
+
+    let flags = &[Flag::build()
+        .with_long_name("stdin")
+        .with_description("Reads from standard input")
+        .with_flag(&stdin)
+        .build()];
+
+    let parameters = &[
+        Parameter::build()
+            .with_long_name("file")
+            .with_description("Reads from a file")
+            .with_parameter(&file)
+            .build(),
+        Parameter::build()
+            .with_long_name("url")
+            .with_description("Reads from a URL")
+            .with_parameter(&url)
+            .build(),
+    ];
+
+    let app: Application = Application::build()
+        .with_flags(flags)
+        .with_parameters(parameters)
+        .with_required_one_of(&["stdin", "file", "url"])
+        .with_command(&default_command)
+        .build();
+
+    execute_captured(&app, args).result
+}
+
+#[test]
+fn flag_required_one_of() {
+    let result = execute_one_of(&[]);
+    assert!(matches!(
+        &result,
+        Err(CommandLineError::MissingOneOf(names)) if names == &["--stdin", "--file", "--url"]
+    ));
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "Expected one of --stdin, --file, --url"
+    );
+
+    execute_one_of(&["--file", "input.txt"]).unwrap();
+
+    let result = execute_one_of(&["--stdin", "--url", "https://example.com"]);
+    assert!(matches!(
+        &result,
+        Err(CommandLineError::ConflictingFlags(names)) if names == &["--stdin", "--url"]
+    ));
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "Only one of --stdin, --url may be given"
+    );
+}
+
+#[test]
+fn flag_required_one_of_default() {
+    let file = ParameterValue::new();
+    let url = ParameterValue::new();
+
+    let parameters = &[
+        Parameter::build()
+            .with_long_name("file")
+            .with_default("input.txt")
+            .with_parameter(&file)
+            .build(),
+        Parameter::build()
+            .with_long_name("url")
+            .with_parameter(&url)
+            .build(),
+    ];
+
+    let app: Application = Application::build()
+        .with_parameters(parameters)
+        .with_required_one_of(&["file", "url"])
+        .with_command(&default_command)
+        .build();
+
+    // only members given on the command line count, a default neither satisfies the group nor conflicts
+    assert!(matches!(
+        execute_captured(&app, &[]).result,
+        Err(CommandLineError::MissingOneOf(names)) if names == ["--file", "--url"]
+    ));
+
+    execute_captured(&app, &["--url", "https://example.com"])
+        .result
+        .unwrap();
+    assert_eq!(file.value(), Some("input.txt"));
+    assert_eq!(url.value(), Some("https://example.com"));
+}
+
+#[test]
+fn flag_unknown_suggestion() {
+    let verbose = FlagValue::new();
+    let output = ParameterValue::new();
+
+    let flags = &[Flag::build()
+        .with_long_name("verbose")
+        .with_description("Prints more")
+        .with_flag(&verbose)
+        .build()];
+    let parameters = &[Parameter::build()
+        .with_long_name("output")
+        .with_description("The output file")
+        .with_parameter(&output)
+        .build()];
+
+    let app: Application = Application::build()
+        .with_flags(flags)
+        .with_parameters(parameters)
+        .with_command(&default_command)
+        .build();
+
+    let Captured { result, err, .. } = execute_captured(&app, &["--verbse"]);
+    assert!(matches!(
+        result,
+        Err(CommandLineError::UnknownArgument(arg)) if arg == "--verbse"
+    ));
+    assert_eq!(err, "Did you mean --verbose?\n");
+
+    let Captured { err, .. } = execute_captured(&app, &["--outptu=a.txt"]);
+    assert_eq!(err, "Did you mean --output?\n");
+
+    // nothing is close enough
+    let Captured { err, .. } = execute_captured(&app, &["--unrelated"]);
+    assert!(err.is_empty());
+
+    let app: Application = Application::build()
+        .with_flags(flags)
+        .with_parameters(parameters)
+        .with_command(&default_command)
+        .with_help_flag(false)
+        .build();
+
+    let Captured { err, .. } = execute_captured(&app, &["--verbse"]);
+    assert!(err.is_empty());
+}
+
+#[test]
+fn flag_explicit_values() {
+    let verbose = FlagValue::new();
+
+    let flags = &[Flag::build()
+        .with_long_name("verbose")
+        .with_description("Prints more")
+        .with_flag(&verbose)
+        .build()];
+
+    let app: Application = Application::build()
+        .with_flags(flags)
+        .with_command(&default_command)
+        .with_flag_values(true)
+        .build();
+
+    verbose.set(true);
+    execute_captured(&app, &["--verbose=false"]).result.unwrap();
+    assert!(!verbose.value());
+
+    execute_captured(&app, &["--verbose=1"]).result.unwrap();
+    assert!(verbose.value());
+
+    execute_captured(&app, &["--verbose=NO"]).result.unwrap();
+    assert!(!verbose.value());
+
+    let result = execute_captured(&app, &["--verbose=maybe"]).result;
+    assert!(matches!(
+        &result,
+        Err(CommandLineError::InvalidValue { name, value, .. }) if name == "--verbose" && value == "maybe"
+    ));
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "Invalid value for --verbose: 'maybe' is not a boolean (true, false, 1, 0, yes or no)"
+    );
+    assert!(!verbose.value());
+}
+
+#[test]
+fn flag_on_set_callback() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static COUNT: AtomicUsize = AtomicUsize::new(0);
+    static COUNT_AT_COMMAND: AtomicUsize = AtomicUsize::new(0);
+
+    fn increment() {
+        COUNT.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn command() {
+        COUNT_AT_COMMAND.store(COUNT.load(Ordering::SeqCst), Ordering::SeqCst);
+    }
+
+    let verbose = FlagValue::new();
+
+    let flags = &[Flag::build()
+        .with_short_name("v")
+        .with_long_name("verbose")
+        .with_flag(&verbose)
+        .with_on_set(increment)
+        .build()];
+
+    let app: Application = Application::build()
+        .with_flags(flags)
+        .with_command(&command)
+        .build();
+
+    execute_captured(&app, &["-v", "-v"]).result.unwrap();
+    assert_eq!(COUNT.load(Ordering::SeqCst), 2);
+    // both fired during parsing, before the command ran
+    assert_eq!(COUNT_AT_COMMAND.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn flag_aliases() {
+    for indexed in [false, true] {
+        let color = FlagValue::new();
+
+        let flags = &[Flag::build()
+            .with_long_name("color")
+            .with_aliases(&["colour"])
+            .with_description("Colors the output")
+            .with_flag(&color)
+            .build()];
+
+        let app: Application = Application::build()
+            .with_name("app")
+            .with_flags(flags)
+            .with_command(&default_command)
+            .with_indexed_lookup(indexed)
+            .build();
+
+        execute_captured(&app, &["--colour"]).result.unwrap();
+        assert!(color.value());
+
+        color.set(false);
+        execute_captured(&app, &["--color"]).result.unwrap();
+        assert!(color.value());
+
+        let help = capture_help(&app, &app);
+        assert!(help.contains("--color"));
+        assert!(!help.contains("--colour"));
+    }
+}
+
+#[test]
+fn flag_aliases_detailed_help() {
+    let color = FlagValue::new();
+
+    let flags = &[Flag::build()
+        .with_long_name("color")
+        .with_aliases(&["colour", "colors"])
+        .with_description("Colors the output")
+        .with_flag(&color)
+        .build()];
+
+    let app: Application = Application::build()
+        .with_name("app")
+        .with_flags(flags)
+        .with_command(&default_command)
+        .with_help_style(HelpStyle::Detailed)
+        .build();
+
+    let help = capture_help(&app, &app);
+    assert!(help.contains("          [aliases: --colour, --colors]\n"));
+}
+
+#[test]
+fn flag_slash_options() {
+    let test = FlagValue::new();
+    let param = ParameterValue::new();
+    let positionals = PositionalValue::new();
+
+    let flags = &[Flag::build()
+        .with_long_name("test")
+        .with_flag(&test)
+        .build()];
+    let parameters = &[Parameter::build()
+        .with_long_name("param")
+        .with_parameter(&param)
+        .build()];
+
+    let app: Application = Application::build()
+        .with_flags(flags)
+        .with_parameters(parameters)
+        .with_positionals(&positionals)
+        .with_command(&default_command)
+        .with_slash_options(true)
+        .build();
+
+    execute_captured(&app, &["/test", "/param:value", "/tmp", "/usr/bin"])
+        .result
+        .unwrap();
+    assert!(test.value());
+    assert_eq!(param.value(), Some("value"));
+    // only known names are options, paths stay arguments
+    assert_eq!(positionals.values(), ["/tmp", "/usr/bin"]);
+
+    assert!(matches!(
+        execute_captured(&app, &["/help"]).result,
+        Ok(RunOutcome::HelpShown)
+    ));
+}
+
+#[test]
+fn flag_slash_options_disabled() {
+    let test = FlagValue::new();
+    let positionals = PositionalValue::new();
+
+    let flags = &[Flag::build()
+        .with_long_name("test")
+        .with_flag(&test)
+        .build()];
+
+    let app: Application = Application::build()
+        .with_flags(flags)
+        .with_positionals(&positionals)
+        .with_command(&default_command)
+        .build();
+
+    execute_captured(&app, &["/test"]).result.unwrap();
+    assert!(!test.value());
+    assert_eq!(positionals.values(), ["/test"]);
+}
+
+#[test]
+fn flag_toggle() {
+    let tabs = FlagValue::new();
+
+    let flags = &[Flag::build()
+        .with_short_name("t")
+        .with_long_name("tabs")
+        .with_toggle(true)
+        .with_flag(&tabs)
+        .build()];
+
+    let app: Application = Application::build()
+        .with_flags(flags)
+        .with_command(&default_command)
+        .build();
+
+    for default in [false, true] {
+        for (args, flipped) in [
+            (&["-t"][..], true),
+            (&["-t", "--tabs"], false),
+            (&["-t", "-t", "-t"], true),
+            (&[], false),
+        ] {
+            tabs.set(default);
+            execute_captured(&app, args).result.unwrap();
+            assert_eq!(tabs.value(), default != flipped, "{:?}", args);
+        }
+    }
+}
+
+#[test]
+fn flag_numeric_short_name() {
+    let one = FlagValue::new();
+    let offset = ParameterValue::new();
+    let positionals = PositionalValue::new();
+
+    let flags = &[Flag::build().with_short_name("1").with_flag(&one).build()];
+    let parameters = &[Parameter::build()
+        .with_long_name("offset")
+        .with_parameter(&offset)
+        .build()];
+
+    let app: Application = Application::build()
+        .with_flags(flags)
+        .with_parameters(parameters)
+        .with_positionals(&positionals)
+        .with_command(&default_command)
+        .build();
+
+    // a registered flag takes precedence over the negative number
+    execute_captured(&app, &["-1", "-2"]).result.unwrap();
+    assert!(one.value());
+    assert_eq!(positionals.values(), ["-2"]);
+
+    // unless a value is expected
+    one.set(false);
+    execute_captured(&app, &["--offset", "-1"]).result.unwrap();
+    assert!(!one.value());
+    assert_eq!(offset.value(), Some("-1"));
+}
+
+#[test]
+fn flag_short_takes_no_value() {
+    for flag_values in [false, true] {
+        let f = FlagValue::new();
+
+        let flags = &[Flag::build()
+            .with_short_name("f")
+            .with_long_name("force")
+            .with_flag(&f)
+            .build()];
+
+        let app: Application = Application::build()
+            .with_flags(flags)
+            .with_command(&default_command)
+            .with_flag_values(flag_values)
+            .build();
+
+        let result = execute_captured(&app, &["-f=1"]).result;
+        if flag_values {
+            assert!(result.is_ok());
+            assert!(f.value());
+        } else {
+            assert!(matches!(
+                result,
+                Err(CommandLineError::FlagTakesNoValue(arg)) if arg == "-f=1"
+            ));
+            assert!(!f.value());
+        }
+    }
+}
+
+#[test]
+fn flag_lookup_public() {
+    use crate::constexpr::{lookup_flag, lookup_parameter};
+
+    let color = FlagValue::new();
+    let output = ParameterValue::new();
+    let token = ParameterValue::new();
+
+    let flags = &[Flag::build()
+        .with_short_name("c")
+        .with_long_name("color")
+        .with_aliases(&["colour"])
+        .with_flag(&color)
+        .build()];
+    let parameters = &[
+        Parameter::build()
+            .with_short_name("o")
+            .with_long_name("output")
+            .with_parameter(&output)
+            .build(),
+        Parameter::build()
+            .with_long_name("token")
+            .with_env("CLIUTIL_TEST_LOOKUP_TOKEN")
+            .with_cli_visible(false)
+            .with_parameter(&token)
+            .build(),
+    ];
+
+    for name in ["--color", "--colour", "-c"] {
+        assert_eq!(
+            lookup_flag(flags, name).map(|flag| flag.long_name()),
+            Some("color")
+        );
+    }
+    assert_eq!(
+        lookup_parameter(parameters, "-o").map(|param| param.long_name()),
+        Some("output")
+    );
+
+    // no prefix matching, and the name must be written as an argument
+    for name in ["--col", "--COLOR", "color", "--", "-", ""] {
+        assert!(lookup_flag(flags, name).is_none(), "{}", name);
+    }
+    assert!(lookup_parameter(parameters, "--token").is_none());
+}
@@ -0,0 +1,752 @@
+use super::*;
+use crate::constexpr::help::{display_width, truncate, CONSOLE_WIOTH, NAME_WIDTH};
+use crate::constexpr::pager::pager_commands;
+
+static HELP_SUBCOMMANDS: &[SubCommand] = &[SubCommand::build()
+    .with_long_name("sub")
+    .with_description("A subcommand")
+    .with_command(&default_command)
+    .build()];
+
+#[test]
+fn help_command_disabled() {
+    let app: Application = Application::build()
+        .with_subcommands(HELP_SUBCOMMANDS)
+        .with_command(&default_command)
+        .with_help_command(false)
+        .build();
+
+    assert!(matches!(
+        execute_captured(&app, &["help"]).result,
+        Err(CommandLineError::UnknownCommand(_))
+    ));
+
+    assert!(matches!(
+        execute_captured(&app, &["--help"]).result,
+        Ok(RunOutcome::HelpShown)
+    ));
+
+    assert!(matches!(
+        execute_captured(&app, &["-h"]).result,
+        Ok(RunOutcome::HelpShown)
+    ));
+}
+
+#[test]
+fn help_flag_disabled() {
+    let app: Application = Application::build()
+        .with_subcommands(HELP_SUBCOMMANDS)
+        .with_command(&default_command)
+        .with_help_flag(false)
+        .build();
+
+    assert!(matches!(
+        execute_captured(&app, &["help"]).result,
+        Ok(RunOutcome::HelpShown)
+    ));
+
+    assert!(matches!(
+        execute_captured(&app, &["--help"]).result,
+        Err(CommandLineError::UnknownArgument(_))
+    ));
+
+    assert!(matches!(
+        execute_captured(&app, &["-h"]).result,
+        Err(CommandLineError::UnknownArgument(_))
+    ));
+}
+
+#[test]
+fn help_disabled() {
+    let app: Application = Application::build()
+        .with_command(&default_command)
+        .with_help(false)
+        .build();
+
+    assert!(matches!(
+        execute_captured(&app, &["help"]).result,
+        Err(CommandLineError::UnknownCommand(_))
+    ));
+
+    assert!(matches!(
+        execute_captured(&app, &["--help"]).result,
+        Err(CommandLineError::UnknownArgument(_))
+    ));
+}
+
+#[test]
+fn help_flag_shadowed_by_user_flag() {
+    let h = FlagValue::new();
+
+    let flags = &[Flag::build()
+        .with_short_name("h")
+        .with_long_name("human")
+        .with_description("Human readable output")
+        .with_flag(&h)
+        .build()];
+
+    let app: Application = Application::build()
+        .with_flags(flags)
+        .with_command(&default_command)
+        .build();
+
+    assert!(matches!(
+        execute_captured(&app, &["-h"]).result,
+        Ok(RunOutcome::Completed(()))
+    ));
+    assert!(h.value());
+}
+
+#[test]
+fn help_value_placeholders() {
+    let f = FlagValue::new();
+    let p = ParameterValue::new();
+    let o = ParameterValue::new();
+
+    let flags = &[Flag::build()
+        .with_short_name("f")
+        .with_long_name("flag")
+        .with_description("A flag")
+        .with_flag(&f)
+        .build()];
+
+    let parameters = &[
+        Parameter::build()
+            .with_short_name("p")
+            .with_long_name("param")
+            .with_description("A parameter")
+            .with_parameter(&p)
+            .build(),
+        Parameter::build()
+            .with_short_name("o")
+            .with_long_name("output")
+            .with_description("An output file")
+            .with_value_name("FILE")
+            .with_parameter(&o)
+            .build(),
+    ];
+
+    let app: Application = Application::build()
+        .with_name("app")
+        .with_flags(flags)
+        .with_parameters(parameters)
+        .with_command(&default_command)
+        .build();
+
+    let help = capture_help(&app, &app);
+
+    assert!(help.contains("Usage: app [FLAGS] [--param <VALUE>] [--output <FILE>]\n"));
+    assert!(help.contains("  -p, --param <VALUE> A parameter\n"));
+    assert!(help.contains("  -o, --output <FILE> An output file\n"));
+    assert!(help.contains("  -f, --flag        A flag\n"));
+}
+
+const LONG_DESCRIPTION: &str = "A flag with a description that is far too long to fit within the \
+    description column of the compact help layout, so it is wrapped in the detailed layout.";
+
+#[test]
+fn help_styles() {
+    let f = FlagValue::new();
+    let old = FlagValue::new();
+    let color = ParameterValue::new();
+
+    let flags = &[
+        Flag::build()
+            .with_short_name("f")
+            .with_long_name("flag")
+            .with_description(LONG_DESCRIPTION)
+            .with_flag(&f)
+            .build(),
+        Flag::build()
+            .with_long_name("old")
+            .with_description(LONG_DESCRIPTION)
+            .with_deprecated(None)
+            .with_flag(&old)
+            .build(),
+    ];
+    let parameters = &[Parameter::build()
+        .with_long_name("color")
+        .with_description(LONG_DESCRIPTION)
+        .with_env("APP_COLOR")
+        .with_allowed_values(&["auto", "never"])
+        .with_parameter(&color)
+        .build()];
+
+    let compact: Application = Application::build()
+        .with_name("app")
+        .with_flags(flags)
+        .with_parameters(parameters)
+        .with_command(&default_command)
+        .build();
+
+    let detailed: Application = Application::build()
+        .with_name("app")
+        .with_flags(flags)
+        .with_parameters(parameters)
+        .with_command(&default_command)
+        .with_help_style(HelpStyle::Detailed)
+        .build();
+
+    let compact_help = capture_help(&compact, &compact);
+    let detailed_help = capture_help(&detailed, &detailed);
+
+    assert!(compact_help.contains("  -f, --flag        A flag with a description"));
+    assert!(!compact_help.contains("wrapped in the detailed layout."));
+    // the notes are kept, and shorten the description so the line still fits
+    assert!(compact_help.contains("  --old             A flag with a description"));
+    assert!(compact_help.contains(" [deprecated]\n"));
+    assert!(compact_help.contains(" (env: APP_COLOR) [possible values: auto, never]\n"));
+    assert!(compact_help.lines().all(|line| line.chars().count() <= 80));
+
+    assert!(detailed_help.contains(
+        "  -f, --flag\n          A flag with a description that is far too long to fit within the\n"
+    ));
+    assert!(detailed_help.contains("          detailed layout.\n"));
+    assert!(detailed_help.len() > compact_help.len());
+    assert!(detailed_help.lines().all(|line| line.chars().count() <= 80));
+}
+
+#[test]
+fn help_style_inherited_by_subcommands() {
+    let subcommands = &[SubCommand::build()
+        .with_long_name("sub")
+        .with_subcommands(HELP_SUBCOMMANDS)
+        .build()];
+
+    let app: Application = Application::build()
+        .with_subcommands(subcommands)
+        .with_help_style(HelpStyle::Detailed)
+        .build();
+
+    let Captured { result, out, .. } = execute_captured(&app, &["sub", "help"]);
+
+    assert!(matches!(result, Ok(RunOutcome::HelpShown)));
+    assert!(out.contains("Subcommands:\n  sub\n          A subcommand\n"));
+}
+
+#[test]
+fn help_wide_names_aligned() {
+    let f = FlagValue::new();
+    let g = FlagValue::new();
+
+    let flags = &[
+        Flag::build()
+            .with_long_name("日本語")
+            .with_description("A wide flag")
+            .with_flag(&f)
+            .build(),
+        Flag::build()
+            .with_long_name("ascii")
+            .with_description("A narrow flag")
+            .with_flag(&g)
+            .build(),
+    ];
+
+    let app: Application = Application::build()
+        .with_flags(flags)
+        .with_command(&default_command)
+        .build();
+
+    let help = capture_help(&app, &app);
+
+    let column = |needle: &str| {
+        let line = help.lines().find(|line| line.contains(needle)).unwrap();
+        display_width(&line[..line.find(needle).unwrap()])
+    };
+
+    assert!(help.contains("  --日本語          A wide flag\n"));
+    assert_eq!(column("A wide flag"), NAME_WIDTH);
+    assert_eq!(column("A narrow flag"), NAME_WIDTH);
+}
+
+#[test]
+fn help_display_width() {
+    assert_eq!(display_width("ascii"), 5);
+    assert_eq!(display_width("日本語"), 6);
+    assert_eq!(display_width("é"), 1);
+    assert_eq!(truncate("日本語", 5), "日本");
+    assert_eq!(truncate("ascii", 10), "ascii");
+}
+
+#[test]
+fn help_after_help() {
+    let app: Application = Application::build()
+        .with_name("app")
+        .with_subcommands(HELP_SUBCOMMANDS)
+        .with_command(&default_command)
+        .with_after_help("Environment:\n  APP_HOME  The data directory")
+        .build();
+
+    let help = capture_help(&app, &app);
+
+    assert!(help.ends_with(
+        "Subcommands:\n  sub               A subcommand\n\nEnvironment:\n  APP_HOME  The data directory\n"
+    ));
+}
+
+#[test]
+fn help_labels() {
+    static LABELS: HelpLabels = HelpLabels {
+        flags: "Options:",
+        usage: "Utilisation:",
+        ..HelpLabels::new()
+    };
+
+    let f = FlagValue::new();
+
+    let flags = &[Flag::build()
+        .with_short_name("f")
+        .with_description("A flag")
+        .with_flag(&f)
+        .build()];
+
+    let app: Application = Application::build()
+        .with_name("app")
+        .with_flags(flags)
+        .with_subcommands(HELP_SUBCOMMANDS)
+        .with_command(&default_command)
+        .with_help_labels(&LABELS)
+        .build();
+
+    let help = capture_help(&app, &app);
+
+    assert!(help.contains("Utilisation: app [FLAGS] [SUBCOMMAND]\n"));
+    assert!(help.contains("\nOptions:\n  -f"));
+    assert!(help.contains("\nSubcommands:\n  sub"));
+    assert!(!help.contains("Flags:"));
+
+    let help = capture_help(&app, &HELP_SUBCOMMANDS[0]);
+    assert!(help.contains("Usage: sub\n"));
+}
+
+#[test]
+fn help_description_paragraphs() {
+    let app: Application = Application::build()
+        .with_name("app")
+        .with_description(
+            "The first paragraph is long enough that it has to be wrapped onto a second line to fit within the console.\n\
+             \n\
+             The second paragraph.",
+        )
+        .with_command(&default_command)
+        .build();
+
+    let help = capture_help(&app, &app);
+
+    assert!(help.starts_with(
+        "app\nThe first paragraph is long enough that it has to be wrapped onto a second line\n\
+         to fit within the console.\n\nThe second paragraph.\n\nUsage: app\n"
+    ));
+}
+
+#[test]
+fn help_description_wrapped_at_width() {
+    let exact = format!("{} {}", "a".repeat(39), "b".repeat(40));
+    let wide = ["漢字漢字"; 12].join(" ");
+
+    for (description, expected) in [
+        // a line that fills the console exactly is kept whole
+        (format!("{} c", exact), vec![exact.clone(), "c".to_string()]),
+        (
+            format!("{}b c", exact),
+            vec!["a".repeat(39), format!("{}b c", "b".repeat(40))],
+        ),
+        // wide characters take up two columns each
+        (
+            wide,
+            vec![["漢字漢字"; 9].join(" "), ["漢字漢字"; 3].join(" ")],
+        ),
+    ] {
+        let app: Application = Application::build()
+            .with_name("app")
+            .with_description(&description)
+            .with_command(&default_command)
+            .build();
+
+        let help = capture_help(&app, &app);
+        let lines: Vec<&str> = help.lines().skip(1).take(expected.len()).collect();
+
+        assert_eq!(lines, expected);
+        for line in lines {
+            assert!(display_width(line) <= CONSOLE_WIOTH, "{}", line);
+        }
+    }
+}
+
+#[test]
+fn help_positional_names() {
+    static FILES: PositionalValue = PositionalValue::new();
+    static COPY: &[SubCommand] = &[SubCommand::build()
+        .with_long_name("copy")
+        .with_description("Copies a file")
+        .with_positionals(&FILES)
+        .with_positional("SRC", true)
+        .with_positional("DST", true)
+        .with_positional("MODE", false)
+        .with_command(&default_command)
+        .build()];
+
+    let app: Application = Application::build()
+        .with_name("app")
+        .with_subcommands(COPY)
+        .build();
+
+    let help = capture_help(&app, &COPY[0]);
+
+    assert!(help.contains("Usage: copy <SRC> <DST> [MODE]\n"));
+    assert!(help.contains("\nArguments:\n  <SRC>\n  <DST>\n  [MODE]\n"));
+    assert!(!help.contains("[ARGS]"));
+}
+
+#[test]
+fn help_long_help() {
+    let f = FlagValue::new();
+    let p = ParameterValue::new();
+
+    let flags = &[Flag::build()
+        .with_short_name("f")
+        .with_description("A flag")
+        .with_long_help("A flag, explained at length.")
+        .with_flag(&f)
+        .build()];
+
+    let parameters = &[Parameter::build()
+        .with_short_name("p")
+        .with_description("A parameter")
+        .with_long_help("A parameter, explained at length.")
+        .with_parameter(&p)
+        .build()];
+
+    let compact: Application = Application::build()
+        .with_name("app")
+        .with_flags(flags)
+        .with_parameters(parameters)
+        .with_command(&default_command)
+        .build();
+
+    let detailed: Application = Application::build()
+        .with_name("app")
+        .with_flags(flags)
+        .with_parameters(parameters)
+        .with_command(&default_command)
+        .with_help_style(HelpStyle::Detailed)
+        .build();
+
+    let compact_help = capture_help(&compact, &compact);
+    let detailed_help = capture_help(&detailed, &detailed);
+
+    assert!(compact_help.contains("  -f                A flag\n"));
+    assert!(compact_help.contains("  -p <VALUE>        A parameter\n"));
+    assert!(!compact_help.contains("explained at length"));
+
+    assert!(detailed_help.contains("  -f\n          A flag, explained at length.\n"));
+    assert!(detailed_help.contains("  -p <VALUE>\n          A parameter, explained at length.\n"));
+}
+
+#[test]
+fn help_subcommand_categories() {
+    let subcommands = &[
+        SubCommand::build()
+            .with_long_name("init")
+            .with_description("Creates a repository")
+            .with_category("Start a working area")
+            .with_command(&default_command)
+            .build(),
+        SubCommand::build()
+            .with_long_name("log")
+            .with_description("Shows commit logs")
+            .with_category("Examine history")
+            .with_command(&default_command)
+            .build(),
+        SubCommand::build()
+            .with_long_name("config")
+            .with_description("Gets and sets options")
+            .with_command(&default_command)
+            .build(),
+        SubCommand::build()
+            .with_long_name("clone")
+            .with_description("Clones a repository")
+            .with_category("Start a working area")
+            .with_command(&default_command)
+            .build(),
+    ];
+
+    let app: Application = Application::build()
+        .with_name("app")
+        .with_subcommands(subcommands)
+        .build();
+
+    let help = capture_help(&app, &app);
+
+    assert!(help.ends_with(
+        "Start a working area:\n  init              Creates a repository\n  clone             Clones a repository\n\n\
+         Examine history:\n  log               Shows commit logs\n\n\
+         Subcommands:\n  config            Gets and sets options\n\n"
+    ));
+}
+
+#[test]
+fn help_render() {
+    let f = FlagValue::new();
+
+    let flags = &[Flag::build()
+        .with_short_name("v")
+        .with_long_name("verbose")
+        .with_description("Prints more output")
+        .with_flag(&f)
+        .build()];
+
+    let app: Application = Application::build()
+        .with_name("app")
+        .with_description("An example application")
+        .with_flags(flags)
+        .with_subcommands(HELP_SUBCOMMANDS)
+        .with_command(&default_command)
+        .build();
+
+    let help = app.render_help();
+    assert_eq!(help, capture_help(&app, &app));
+    assert!(help.contains("Flags:\n  -v, --verbose"));
+
+    assert!(HELP_SUBCOMMANDS[0]
+        .render_help()
+        .starts_with("sub\nA subcommand\n"));
+}
+
+#[test]
+fn help_no_trailing_whitespace() {
+    let f = FlagValue::new();
+    let g = FlagValue::new();
+    let p = ParameterValue::new();
+
+    let flags = &[
+        Flag::build().with_short_name("f").with_flag(&f).build(),
+        Flag::build()
+            .with_long_name("old")
+            .with_deprecated(None)
+            .with_flag(&g)
+            .build(),
+    ];
+
+    let parameters = &[Parameter::build()
+        .with_long_name("param")
+        .with_description("Ends with a space ")
+        .with_parameter(&p)
+        .build()];
+
+    let subcommands = &[SubCommand::build()
+        .with_long_name("sub")
+        .with_command(&default_command)
+        .build()];
+
+    for style in [HelpStyle::Compact, HelpStyle::Detailed] {
+        let app: Application = Application::build()
+            .with_name("app")
+            .with_flags(flags)
+            .with_parameters(parameters)
+            .with_subcommands(subcommands)
+            .with_command(&default_command)
+            .with_help_style(style)
+            .build();
+
+        let help = capture_help(&app, &app);
+
+        assert!(help.contains("  -f\n"));
+        for line in help.lines() {
+            assert!(!line.ends_with(' '), "{:?} ends with whitespace", line);
+        }
+    }
+
+    // a nameless application leaves no gap after the usage label
+    let app: Application = Application::build().with_command(&default_command).build();
+    let help = capture_help(&app, &app);
+    assert!(help.contains("Usage:\n"), "{:?}", help);
+    for line in help.lines() {
+        assert!(!line.ends_with(' '), "{:?} ends with whitespace", line);
+    }
+
+    let app: Application = Application::build()
+        .with_flags(flags)
+        .with_command(&default_command)
+        .build();
+    assert!(capture_help(&app, &app).contains("Usage: [FLAGS]\n"));
+}
+
+#[test]
+fn help_layout_widths() {
+    let app: Application = Application::build()
+        .with_subcommands(HELP_SUBCOMMANDS)
+        .with_command(&default_command)
+        .build();
+
+    assert_eq!(
+        app.help_name_width() + app.help_description_width(),
+        CONSOLE_WIOTH
+    );
+
+    let help = app.render_help();
+    let line = help
+        .lines()
+        .find(|line| line.contains("A subcommand"))
+        .unwrap();
+    assert_eq!(line.find("A subcommand"), Some(app.help_name_width()));
+}
+
+#[test]
+fn help_layout_widths_configured() {
+    let app: Application = Application::build()
+        .with_subcommands(HELP_SUBCOMMANDS)
+        .with_command(&default_command)
+        .with_help_width(50)
+        .build();
+
+    assert_eq!(app.help_name_width() + app.help_description_width(), 50);
+    assert!(app
+        .render_help()
+        .lines()
+        .all(|line| display_width(line) <= 50));
+
+    // the layout of format_help follows its own width
+    let options = HelpOptions {
+        width: 40,
+        ..HelpOptions::new()
+    };
+    assert_eq!(options.name_width() + options.description_width(), 40);
+    assert!(app
+        .format_help(options)
+        .lines()
+        .all(|line| display_width(line) <= 40));
+}
+
+#[test]
+fn help_pager_bypassed() {
+    let app: Application = Application::build()
+        .with_name("app")
+        .with_command(&default_command)
+        .with_pager(true)
+        .build();
+
+    let Captured { result, out, .. } = execute_captured(&app, &["--help"]);
+    assert!(matches!(result, Ok(RunOutcome::HelpShown)));
+    assert_eq!(out, app.render_help());
+
+    let (mut out, mut err) = (Vec::new(), Vec::new());
+    let result = app.execute_into(["--help"].iter(), &mut out, &mut err);
+    assert!(matches!(result, Ok(RunOutcome::HelpShown)));
+    assert_eq!(String::from_utf8(out).unwrap(), app.render_help());
+}
+
+#[test]
+fn help_pager_commands() {
+    assert_eq!(pager_commands(None), [&["less", "-R"][..], &["more"]]);
+    assert_eq!(pager_commands(Some("  ")), [&["less", "-R"][..], &["more"]]);
+    assert_eq!(pager_commands(Some("most -s")), [["most", "-s"]]);
+}
+
+#[test]
+fn help_plain_when_not_terminal() {
+    let verbose = FlagValue::new();
+    let flags = &[Flag::build()
+        .with_short_name("v")
+        .with_long_name("verbose")
+        .with_description("Prints every step of the build, including the ones that are normally too noisy to show")
+        .with_flag(&verbose)
+        .build()];
+
+    let app: Application = Application::build()
+        .with_name("app")
+        .with_description(
+            "An application whose help is written to a writer that is not a terminal.",
+        )
+        .with_flags(flags)
+        .with_subcommands(HELP_SUBCOMMANDS)
+        .with_command(&default_command)
+        .with_color(ColorChoice::Always)
+        .with_pager(true)
+        .build();
+
+    let Captured { result, out, .. } = execute_captured(&app, &["--help"]);
+    assert!(matches!(result, Ok(RunOutcome::HelpShown)));
+    assert_eq!(out, app.render_help());
+    assert!(out.is_ascii());
+    assert!(!out.contains('\x1b'));
+    assert!(out.lines().all(|line| display_width(line) <= CONSOLE_WIOTH));
+}
+
+#[test]
+fn help_format_with_options() {
+    let verbose = FlagValue::new();
+    let flags = &[Flag::build()
+        .with_short_name("v")
+        .with_long_name("verbose")
+        .with_description("Prints every step of the build")
+        .with_flag(&verbose)
+        .build()];
+
+    let app: Application = Application::build()
+        .with_name("app")
+        .with_description("A description long enough to be wrapped in a narrow console.")
+        .with_flags(flags)
+        .with_command(&default_command)
+        .build();
+
+    let options = HelpOptions {
+        width: 40,
+        ..HelpOptions::new()
+    };
+    let help = app.format_help(options);
+    assert!(help.starts_with(
+        "app\nA description long enough to be wrapped\nin a narrow console.\n\nUsage: app [FLAGS]\n\nFlags:\n  -v, --verbose     Prints every step of\n"
+    ));
+    assert!(help.lines().all(|line| display_width(line) <= 40));
+    assert!(!help.contains('\x1b'));
+
+    assert_eq!(app.format_help(HelpOptions::new()), app.render_help());
+
+    let help = app.format_help(HelpOptions {
+        width: 40,
+        color: true,
+        style: HelpStyle::Detailed,
+    });
+    assert!(help.contains("\x1b[1mUsage:\x1b[0m app [FLAGS]\n"));
+    assert!(help.contains(
+        "\x1b[1mFlags:\x1b[0m\n  -v, --verbose\n          Prints every step of the build\n"
+    ));
+}
+
+#[test]
+fn help_parameter_values() {
+    let color = ParameterValue::new();
+
+    let parameters = &[Parameter::build()
+        .with_long_name("color")
+        .with_description("When to color the output")
+        .with_allowed_values(&["auto", "always", "never"])
+        .with_implicit_value("always")
+        .with_default("auto")
+        .with_parameter(&color)
+        .build()];
+
+    // wide enough for every note, so none is cut off
+    let app: Application = Application::build()
+        .with_parameters(parameters)
+        .with_command(&default_command)
+        .with_help_width(140)
+        .build();
+    let help = capture_help(&app, &app);
+    assert!(help.contains(
+        "When to color the output [possible values: auto, always, never] [implicit value: always] [default: auto]\n"
+    ));
+
+    let app: Application = Application::build()
+        .with_parameters(parameters)
+        .with_command(&default_command)
+        .with_help_style(HelpStyle::Detailed)
+        .build();
+    let help = capture_help(&app, &app);
+    // each on a line of its own under the description
+    let lines: Vec<&str> = help.lines().map(str::trim).collect();
+    assert!(lines.contains(&"[possible values: auto, always, never]"));
+    assert!(lines.contains(&"[implicit value: always]"));
+    assert!(lines.contains(&"[default: auto]"));
+}
@@ -1,6 +1,72 @@
 use super::*;
 
+mod application;
+#[cfg(feature = "async")]
+mod asynchronous;
+mod builders;
+mod completion;
+mod errors;
+// the original parsing tests ignore the `Result` of `execute` and check the parsed values instead
+#[allow(unused_must_use)]
 mod flags;
+mod help;
+#[allow(unused_must_use)]
 mod parameters;
+mod positionals;
+mod subcommands;
+mod tokenize;
+mod values;
 
 fn default_command() {}
+
+/// The result of an execution along with everything it wrote.
+struct Captured<R> {
+    result: Result<RunOutcome<R>, CommandLineError>,
+    out: String,
+    err: String,
+}
+
+/// Executes `app` with `args`, capturing everything written to the output and error streams.
+fn execute_captured<R>(app: &Application<R>, args: &[&str]) -> Captured<R> {
+    let (mut out, mut err) = (Vec::new(), Vec::new());
+    let result = app.execute_with(args.iter(), &mut app.context(&mut out, &mut err));
+    Captured {
+        result,
+        out: String::from_utf8(out).unwrap(),
+        err: String::from_utf8(err).unwrap(),
+    }
+}
+
+/// Serializes the tests that set environment variables, which every thread of the process shares.
+static ENV_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+/// Runs `f` with the environment variables `vars` set, removing them afterwards even if `f` panics.
+///
+/// Only one test sets variables at a time, so tests never race each other over the environment.
+fn with_env<T>(vars: &[(&str, &str)], f: impl FnOnce() -> T) -> T {
+    struct Unset<'v>(&'v [(&'v str, &'v str)]);
+
+    impl Drop for Unset<'_> {
+        fn drop(&mut self) {
+            for (name, _) in self.0 {
+                std::env::remove_var(name)
+            }
+        }
+    }
+
+    let _lock = ENV_LOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let _unset = Unset(vars);
+    for (name, value) in vars {
+        std::env::set_var(name, value)
+    }
+    f()
+}
+
+/// Renders the help of `command` using the settings of `app`.
+fn capture_help<R>(app: &Application<R>, command: &impl Command<R>) -> String {
+    let (mut out, mut err) = (Vec::new(), Vec::new());
+    command.print_help(&mut app.context(&mut out, &mut err));
+    String::from_utf8(out).unwrap()
+}
@@ -59,3 +59,1071 @@ fn parameter_parsing_2() {
 
     assert!(f.value().is_some() && f.value().unwrap() == "hello" && g.value().is_none());
 }
+
+fn validate_port(value: &str) -> Result<(), String> {
+    match value.parse::<u16>() {
+        Ok(port) if port != 0 => Ok(()),
+        _ => Err(format!("{} is not a valid port", value)),
+    }
+}
+
+#[test]
+fn parameter_validation_1() {
+    let port = ParameterValue::new();
+
+    let parameters = &[Parameter::build()
+        .with_long_name("port")
+        .with_description("A port")
+        .with_parameter(&port)
+        .with_validator(validate_port)
+        .build()];
+
+    let app: Application = Application::build()
+        .with_parameters(parameters)
+        .with_command(&default_command)
+        .build();
+
+    let args = &["--port=8080"];
+
+    app.execute(args.iter()).unwrap();
+
+    assert_eq!(port.value(), Some("8080"));
+}
+
+#[test]
+fn parameter_validation_2() {
+    let port = ParameterValue::new();
+
+    let parameters = &[Parameter::build()
+        .with_short_name("p")
+        .with_long_name("port")
+        .with_description("A port")
+        .with_parameter(&port)
+        .with_validator(validate_port)
+        .build()];
+
+    let app: Application = Application::build()
+        .with_parameters(parameters)
+        .with_command(&default_command)
+        .build();
+
+    let args = &["-p", "70000"];
+
+    match app.execute(args.iter()) {
+        Err(CommandLineError::ValidationFailed { name, message }) => {
+            assert_eq!(name, "--port");
+            assert_eq!(message, "70000 is not a valid port");
+        }
+        _ => panic!("expected validation failure"),
+    }
+
+    assert!(port.value().is_none());
+}
+
+#[test]
+fn parameter_cluster() {
+    let f = FlagValue::new();
+    let p = ParameterValue::new();
+
+    let flags = &[Flag::build()
+        .with_short_name("f")
+        .with_description("A flag")
+        .with_flag(&f)
+        .build()];
+
+    let parameters = &[Parameter::build()
+        .with_short_name("p")
+        .with_description("A parameter")
+        .with_parameter(&p)
+        .build()];
+
+    let app: Application = Application::build()
+        .with_flags(flags)
+        .with_parameters(parameters)
+        .with_command(&default_command)
+        .build();
+
+    let args = &["-fp", "value"];
+
+    app.execute(args.iter()).unwrap();
+
+    assert!(f.value() && p.value() == Some("value"));
+}
+
+#[test]
+fn parameter_separate_long_value() {
+    let offset = ParameterValue::new();
+
+    let parameters = &[Parameter::build()
+        .with_long_name("offset")
+        .with_description("An offset")
+        .with_parameter(&offset)
+        .build()];
+
+    let app: Application = Application::build()
+        .with_parameters(parameters)
+        .with_command(&default_command)
+        .build();
+
+    let args = &["--offset", "-5"];
+
+    app.execute(args.iter()).unwrap();
+
+    assert_eq!(offset.value(), Some("-5"));
+}
+
+#[test]
+fn parameter_value_with_equals() {
+    for (arg, expected) in [
+        ("--expr=a=b", "a=b"),
+        ("--expr==y", "=y"),
+        ("--expr=a==", "a=="),
+        ("--expr==", "="),
+        ("--expr=", ""),
+    ] {
+        let expr = ParameterValue::new();
+
+        let parameters = &[Parameter::build()
+            .with_long_name("expr")
+            .with_parameter(&expr)
+            .build()];
+
+        let app: Application = Application::build()
+            .with_parameters(parameters)
+            .with_command(&default_command)
+            .build();
+
+        app.execute([arg].iter()).unwrap();
+
+        assert_eq!(expr.value(), Some(expected), "{}", arg);
+    }
+}
+
+#[test]
+fn parameter_separate_long_value_missing() {
+    let offset = ParameterValue::new();
+
+    let parameters = &[Parameter::build()
+        .with_long_name("offset")
+        .with_description("An offset")
+        .with_parameter(&offset)
+        .build()];
+
+    let app: Application = Application::build()
+        .with_parameters(parameters)
+        .with_command(&default_command)
+        .build();
+
+    let args = &["--offset"];
+
+    assert!(matches!(
+        app.execute(args.iter()),
+        Err(CommandLineError::ExpectedValue { .. })
+    ));
+}
+
+#[test]
+fn parameter_stdin() {
+    let input = ParameterValue::new();
+    let f = FlagValue::new();
+
+    let flags = &[Flag::build()
+        .with_long_name("flag")
+        .with_description("A flag without a short name")
+        .with_flag(&f)
+        .build()];
+
+    let parameters = &[Parameter::build()
+        .with_short_name("i")
+        .with_long_name("input")
+        .with_description("An input file")
+        .with_parameter(&input)
+        .build()];
+
+    let app: Application = Application::build()
+        .with_flags(flags)
+        .with_parameters(parameters)
+        .with_command(&default_command)
+        .build();
+
+    let args = &["--input", "-"];
+
+    app.execute(args.iter()).unwrap();
+
+    assert_eq!(input.value(), Some("-"));
+    assert!(input.is_stdin());
+    assert!(!f.value());
+}
+
+#[test]
+fn parameter_not_stdin() {
+    let input = ParameterValue::new();
+
+    let parameters = &[Parameter::build()
+        .with_short_name("i")
+        .with_description("An input file")
+        .with_parameter(&input)
+        .build()];
+
+    let app: Application = Application::build()
+        .with_parameters(parameters)
+        .with_command(&default_command)
+        .build();
+
+    assert!(!input.is_stdin());
+
+    let args = &["-i", "file.txt"];
+
+    app.execute(args.iter()).unwrap();
+
+    assert!(!input.is_stdin());
+}
+
+#[test]
+fn parameter_deprecated() {
+    let p = ParameterValue::new();
+
+    let parameters = &[Parameter::build()
+        .with_short_name("p")
+        .with_long_name("param")
+        .with_description("A parameter")
+        .with_deprecated(Some("it has no effect"))
+        .with_parameter(&p)
+        .build()];
+
+    let app: Application = Application::build()
+        .with_parameters(parameters)
+        .with_command(&default_command)
+        .with_help_style(HelpStyle::Detailed)
+        .build();
+
+    let Captured { err, .. } = execute_captured(&app, &["-p", "value"]);
+
+    assert_eq!(p.value(), Some("value"));
+    assert_eq!(err, "warning: --param is deprecated: it has no effect\n");
+
+    let help = capture_help(&app, &app);
+    assert!(help.contains(
+        "  -p, --param <VALUE>\n          A parameter\n          [deprecated: it has no effect]\n\n"
+    ));
+}
+
+#[test]
+fn parameter_implicit_value() {
+    fn run(
+        args: &[&str],
+    ) -> (
+        Result<RunOutcome<()>, CommandLineError>,
+        Option<String>,
+        Vec<String>,
+    ) {
+        let color = ParameterValue::new();
+        let positionals = PositionalValue::new();
+
+        let parameters = &[Parameter::build()
+            .with_short_name("c")
+            .with_long_name("color")
+            .with_description("When to color the output")
+            .with_allowed_values(&["auto", "always", "never"])
+            .with_implicit_value("always")
+            .with_parameter(&color)
+            .build()];
+
+        let app: Application = Application::build()
+            .with_parameters(parameters)
+            .with_positionals(&positionals)
+            .with_command(&default_command)
+            .build();
+
+        let result = execute_captured(&app, args).result;
+        (
+            result,
+            color.value().map(str::to_string),
+            positionals.values().to_vec(),
+        )
+    }
+
+    let (result, color, _) = run(&["--color"]);
+    assert!(matches!(result, Ok(RunOutcome::Completed(()))));
+    assert_eq!(color.as_deref(), Some("always"));
+
+    let (result, color, _) = run(&["--color=never"]);
+    assert!(matches!(result, Ok(RunOutcome::Completed(()))));
+    assert_eq!(color.as_deref(), Some("never"));
+
+    let (result, color, positionals) = run(&["--color", "auto", "file"]);
+    assert!(matches!(result, Ok(RunOutcome::Completed(()))));
+    assert_eq!(color.as_deref(), Some("auto"));
+    assert_eq!(positionals, ["file"]);
+
+    let (result, color, positionals) = run(&["-c", "file"]);
+    assert!(matches!(result, Ok(RunOutcome::Completed(()))));
+    assert_eq!(color.as_deref(), Some("always"));
+    assert_eq!(positionals, ["file"]);
+
+    let (result, color, _) = run(&["--color=blah"]);
+    assert!(matches!(
+        result,
+        Err(CommandLineError::ValidationFailed { name, message })
+            if name == "--color" && message == "'blah' is not one of auto, always, never"
+    ));
+    assert_eq!(color, None);
+}
+
+#[test]
+fn parameter_without_implicit_value() {
+    let p = ParameterValue::new();
+
+    let parameters = &[Parameter::build()
+        .with_long_name("param")
+        .with_description("A parameter")
+        .with_parameter(&p)
+        .build()];
+
+    let app: Application = Application::build()
+        .with_parameters(parameters)
+        .with_command(&default_command)
+        .build();
+
+    assert!(matches!(
+        execute_captured(&app, &["--param"]).result,
+        Err(CommandLineError::ExpectedValue { .. })
+    ));
+}
+
+#[test]
+fn parameter_expected_value_message() {
+    let p = ParameterValue::new();
+    let q = ParameterValue::new();
+
+    let parameters = &[
+        Parameter::build()
+            .with_short_name("p")
+            .with_long_name("param")
+            .with_description("A parameter")
+            .with_value_name("FILE")
+            .with_parameter(&p)
+            .build(),
+        Parameter::build()
+            .with_long_name("output")
+            .with_description("An output")
+            .with_parameter(&q)
+            .build(),
+    ];
+
+    let app: Application = Application::build()
+        .with_parameters(parameters)
+        .with_command(&default_command)
+        .build();
+
+    let Err(err) = execute_captured(&app, &["-p"]).result else {
+        panic!("expected an error");
+    };
+    assert!(matches!(
+        &err,
+        CommandLineError::ExpectedValue { arg, names, value_name }
+            if arg == "-p" && names == "-p, --param" && value_name == "FILE"
+    ));
+    assert_eq!(err.to_string(), "Expected <FILE> after -p, --param");
+
+    let Err(err) = execute_captured(&app, &["--output"]).result else {
+        panic!("expected an error");
+    };
+    assert_eq!(err.to_string(), "Expected <VALUE> after --output");
+}
+
+#[test]
+fn parameter_config_source() {
+    let output = ParameterValue::new();
+    let level = ParameterValue::new();
+    let name = ParameterValue::new();
+
+    let parameters = &[
+        Parameter::build()
+            .with_long_name("output")
+            .with_description("An output file")
+            .with_parameter(&output)
+            .build(),
+        Parameter::build()
+            .with_long_name("level")
+            .with_description("A level")
+            .with_parameter(&level)
+            .build(),
+        Parameter::build()
+            .with_long_name("name")
+            .with_description("A name")
+            .with_parameter(&name)
+            .build(),
+    ];
+
+    let app: Application = Application::build()
+        .with_parameters(parameters)
+        .with_command(&default_command)
+        .with_config_source(|name| match name {
+            "output" => Some("configured.txt".to_string()),
+            "level" => Some("3".to_string()),
+            _ => None,
+        })
+        .build();
+
+    execute_captured(&app, &["--level", "1"]).result.unwrap();
+
+    assert_eq!(output.value(), Some("configured.txt"));
+    assert_eq!(level.value(), Some("1"));
+    assert_eq!(name.value(), None);
+}
+
+#[test]
+fn parameter_fallback_precedence() {
+    let values = [(); 4].map(|_| ParameterValue::new());
+    let names = ["given", "env", "configured", "default"];
+
+    let parameters: Vec<Parameter> = names
+        .iter()
+        .zip(&values)
+        .map(|(name, value)| {
+            Parameter::build()
+                .with_long_name(name)
+                .with_env(match *name {
+                    "given" | "env" => "CLIUTIL_TEST_PRECEDENCE",
+                    _ => "CLIUTIL_TEST_PRECEDENCE_UNSET",
+                })
+                .with_default("default")
+                .with_parameter(value)
+                .build()
+        })
+        .collect();
+
+    let app: Application = Application::build()
+        .with_parameters(&parameters)
+        .with_command(&default_command)
+        .with_config_source(|name| (name != "default").then(|| "configured".to_string()))
+        .build();
+
+    with_env(&[("CLIUTIL_TEST_PRECEDENCE", "env")], || {
+        execute_captured(&app, &["--given", "given"]).result
+    })
+    .unwrap();
+
+    // the command line, then the environment, then the config source, then the default
+    for (name, value) in names.iter().zip(&values) {
+        assert_eq!(value.value(), Some(*name));
+    }
+}
+
+#[test]
+fn parameter_fallback_not_present() {
+    static SOURCES: &[&(dyn ValueSource + Sync)] = &[&DefaultSource::new(&[("sourced", "x")])];
+
+    let values = [(); 5].map(|_| ParameterValue::new());
+    let names = ["defaulted", "env", "sourced", "configured", "format"];
+
+    let parameters: Vec<Parameter> = names
+        .iter()
+        .zip(&values)
+        .map(|(name, value)| {
+            let param = Parameter::build()
+                .with_long_name(name)
+                .with_parameter(value);
+            match *name {
+                "format" => param,
+                "defaulted" => param.with_default("x").with_requires(&["format"]),
+                "env" => param
+                    .with_env("CLIUTIL_TEST_FALLBACK")
+                    .with_requires(&["format"]),
+                _ => param.with_requires(&["format"]),
+            }
+            .build()
+        })
+        .collect();
+
+    let app: Application = Application::build()
+        .with_parameters(&parameters)
+        .with_command(&default_command)
+        .with_value_sources(SOURCES)
+        .with_config_source(|name| (name == "configured").then(|| "x".to_string()))
+        .build();
+
+    // values from outside the command line are not given, so they require nothing
+    with_env(&[("CLIUTIL_TEST_FALLBACK", "x")], || {
+        execute_captured(&app, &[]).result
+    })
+    .unwrap();
+    for value in &values[..4] {
+        assert_eq!(value.value(), Some("x"));
+        assert!(!value.is_given());
+    }
+
+    assert!(matches!(
+        execute_captured(&app, &["--defaulted", "y"]).result,
+        Err(CommandLineError::MissingDependency { flag, requires })
+            if flag == "--defaulted" && requires == "--format"
+    ));
+}
+
+#[test]
+fn parameter_required() {
+    let output = ParameterValue::new();
+
+    let parameters = &[Parameter::build()
+        .with_long_name("output")
+        .with_description("An output file")
+        .with_required(true)
+        .with_parameter(&output)
+        .build()];
+
+    let app: Application = Application::build()
+        .with_parameters(parameters)
+        .with_command(&default_command)
+        .build();
+
+    assert!(matches!(
+        execute_captured(&app, &[]).result,
+        Err(CommandLineError::MissingRequiredParameter(param)) if param == "--output"
+    ));
+
+    execute_captured(&app, &["--output", "out.txt"])
+        .result
+        .unwrap();
+    assert_eq!(output.value(), Some("out.txt"));
+}
+
+/// Executes `app` with no arguments, reading prompted values from `input`.
+fn execute_prompted(
+    app: &Application,
+    input: &str,
+) -> (Result<RunOutcome<()>, CommandLineError>, String) {
+    let (mut out, mut err) = (Vec::new(), Vec::new());
+    let mut input = input.as_bytes();
+    let mut ctx = app.context(&mut out, &mut err);
+    ctx.input = Some(&mut input);
+    let result = app.execute_with(std::iter::empty::<&str>(), &mut ctx);
+    (result, String::from_utf8(err).unwrap())
+}
+
+#[test]
+fn parameter_required_prompt() {
+    let output = ParameterValue::new();
+
+    let parameters = &[Parameter::build()
+        .with_long_name("output")
+        .with_description("An output file")
+        .with_required(true)
+        .with_parameter(&output)
+        .build()];
+
+    let app: Application = Application::build()
+        .with_parameters(parameters)
+        .with_command(&default_command)
+        .with_interactive_prompts(true)
+        .build();
+
+    let (result, err) = execute_prompted(&app, "typed.txt\n");
+    result.unwrap();
+    assert_eq!(err, "Enter value for --output: ");
+    assert_eq!(output.value(), Some("typed.txt"));
+
+    // the input ends before a value is entered
+    let output = ParameterValue::new();
+    let parameters = &[Parameter::build()
+        .with_long_name("output")
+        .with_description("An output file")
+        .with_required(true)
+        .with_parameter(&output)
+        .build()];
+    let app: Application = Application::build()
+        .with_parameters(parameters)
+        .with_command(&default_command)
+        .with_interactive_prompts(true)
+        .build();
+
+    let (result, _) = execute_prompted(&app, "");
+    assert!(matches!(
+        result,
+        Err(CommandLineError::MissingRequiredParameter(_))
+    ));
+}
+
+#[test]
+fn parameter_map() {
+    let defines = MapParameterValue::new();
+
+    let parameters = &[Parameter::build()
+        .with_short_name("D")
+        .with_long_name("define")
+        .with_description("Defines a variable")
+        .with_value_name("KEY=VALUE")
+        .with_map_parameter(&defines)
+        .build()];
+
+    let app: Application = Application::build()
+        .with_parameters(parameters)
+        .with_command(&default_command)
+        .build();
+
+    execute_captured(&app, &["-D", "a=1", "--define", "b=2=3", "-D", "a=4"])
+        .result
+        .unwrap();
+
+    assert_eq!(
+        defines.entries(),
+        &[
+            ("a".to_string(), "1".to_string()),
+            ("b".to_string(), "2=3".to_string()),
+            ("a".to_string(), "4".to_string()),
+        ]
+    );
+    assert_eq!(defines.get("a"), Some("4"));
+    assert_eq!(defines.get("b"), Some("2=3"));
+    assert_eq!(defines.get("c"), None);
+    assert_eq!(
+        app.collect_values()["define"],
+        ArgValue::Multi(vec!["a=1".into(), "b=2=3".into(), "a=4".into()])
+    );
+}
+
+#[test]
+fn parameter_map_malformed() {
+    let defines = MapParameterValue::new();
+
+    let parameters = &[Parameter::build()
+        .with_short_name("D")
+        .with_description("Defines a variable")
+        .with_map_parameter(&defines)
+        .build()];
+
+    let app: Application = Application::build()
+        .with_parameters(parameters)
+        .with_command(&default_command)
+        .build();
+
+    let result = execute_captured(&app, &["-D", "noeq"]).result;
+    assert!(matches!(
+        &result,
+        Err(CommandLineError::InvalidValue { name, value, .. }) if name == "-D" && value == "noeq"
+    ));
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "Invalid value for -D: 'noeq' is not KEY=VALUE"
+    );
+    assert!(defines.entries().is_empty());
+}
+
+#[test]
+fn parameter_multi() {
+    let tags = MultiParameterValue::new();
+
+    let parameters = &[Parameter::build()
+        .with_short_name("t")
+        .with_long_name("tag")
+        .with_description("Adds a tag")
+        .with_multi_parameter(&tags)
+        .build()];
+
+    let app: Application = Application::build()
+        .with_parameters(parameters)
+        .with_command(&default_command)
+        .build();
+
+    execute_captured(&app, &["--tag", "a,b", "-t", "c", "--tag="])
+        .result
+        .unwrap();
+
+    assert_eq!(tags.values(), &["a,b", "c", ""]);
+    assert_eq!(
+        app.collect_values()["tag"],
+        ArgValue::Multi(vec!["a,b".into(), "c".into(), "".into()])
+    );
+}
+
+#[test]
+fn parameter_value_delimiter() {
+    let tags = MultiParameterValue::new();
+
+    let parameters = &[Parameter::build()
+        .with_long_name("tags")
+        .with_description("Sets the tags")
+        .with_multi_parameter(&tags)
+        .with_value_delimiter(',')
+        .with_allowed_values(&["a", "b", "c"])
+        .build()];
+
+    let app: Application = Application::build()
+        .with_parameters(parameters)
+        .with_command(&default_command)
+        .build();
+
+    execute_captured(&app, &["--tags", "a,b,c"]).result.unwrap();
+    assert_eq!(tags.values(), &["a", "b", "c"]);
+
+    // empty segments, including a trailing one, are dropped
+    execute_captured(&app, &["--tags=a,,b,"]).result.unwrap();
+    assert_eq!(tags.values(), &["a", "b", "c", "a", "b"]);
+
+    // a rejected segment stores none of the value
+    assert!(matches!(
+        execute_captured(&app, &["--tags", "c,d"]).result,
+        Err(CommandLineError::ValidationFailed { .. })
+    ));
+    assert_eq!(tags.values(), &["a", "b", "c", "a", "b"]);
+}
+
+#[test]
+fn parameter_numeric() {
+    for (args, expected) in [
+        (&["--level", "5"][..], Some(5)),
+        (&["--level", "+2"], Some(2)),
+        (&["--level", "5", "--level", "+2"], Some(7)),
+        (&["--level", "5", "--level", "-1"], Some(4)),
+        (&["--level=3", "-l", "+2", "--level=-10"], Some(-5)),
+        (&["--level", "+2", "--level", "5"], Some(5)),
+        (&[], None),
+    ] {
+        let level = NumericParameterValue::new();
+
+        let parameters = &[Parameter::build()
+            .with_short_name("l")
+            .with_long_name("level")
+            .with_numeric_parameter(&level)
+            .build()];
+
+        let app: Application = Application::build()
+            .with_parameters(parameters)
+            .with_command(&default_command)
+            .build();
+
+        execute_captured(&app, args).result.unwrap();
+        assert_eq!(level.value(), expected, "{:?}", args);
+        assert_eq!(
+            app.collect_values()["level"],
+            ArgValue::Param(expected.map(|value| value.to_string()))
+        );
+    }
+}
+
+#[test]
+fn parameter_numeric_invalid() {
+    let level = NumericParameterValue::new();
+
+    let parameters = &[Parameter::build()
+        .with_long_name("level")
+        .with_numeric_parameter(&level)
+        .build()];
+
+    let app: Application = Application::build()
+        .with_parameters(parameters)
+        .with_command(&default_command)
+        .build();
+
+    for value in ["high", "+", "1.5", "++1"] {
+        assert!(matches!(
+            execute_captured(&app, &["--level", value]).result,
+            Err(CommandLineError::InvalidValue { name, .. }) if name == "--level"
+        ));
+    }
+
+    execute_captured(&app, &["--level", &i64::MAX.to_string()])
+        .result
+        .unwrap();
+    assert!(matches!(
+        execute_captured(&app, &["--level", "+1"]).result,
+        Err(CommandLineError::InvalidValue { .. })
+    ));
+    assert_eq!(level.value(), Some(i64::MAX));
+}
+
+#[test]
+fn parameter_env() {
+    fn config_source(name: &str) -> Option<String> {
+        (name == "token").then(|| "configured".to_string())
+    }
+
+    for (args, expected) in [(&["--token", "given"][..], "given"), (&[], "from-env")] {
+        let token = ParameterValue::new();
+
+        let parameters = &[Parameter::build()
+            .with_long_name("token")
+            .with_description("The token")
+            .with_env("CLIUTIL_TEST_TOKEN")
+            .with_parameter(&token)
+            .build()];
+
+        let app: Application = Application::build()
+            .with_parameters(parameters)
+            .with_config_source(config_source)
+            .with_command(&default_command)
+            .build();
+
+        with_env(&[("CLIUTIL_TEST_TOKEN", "from-env")], || {
+            execute_captured(&app, args).result
+        })
+        .unwrap();
+        assert_eq!(token.value(), Some(expected));
+        // a value from the environment is not given on the command line
+        assert!(token.has_value());
+        assert_eq!(token.is_given(), !args.is_empty());
+        assert!(app
+            .render_help()
+            .contains("  --token <VALUE>   The token (env: CLIUTIL_TEST_TOKEN)\n"));
+    }
+}
+
+#[test]
+fn parameter_env_only() {
+    let secret = ParameterValue::new();
+
+    let parameters = &[Parameter::build()
+        .with_long_name("secret")
+        .with_description("The secret")
+        .with_env("CLIUTIL_TEST_SECRET")
+        .with_cli_visible(false)
+        .with_parameter(&secret)
+        .build()];
+
+    let app: Application = Application::build()
+        .with_parameters(parameters)
+        .with_command(&default_command)
+        .build();
+
+    let Captured { result, err, .. } = execute_captured(&app, &["--secret", "x"]);
+    assert!(matches!(result, Err(CommandLineError::UnknownArgument(arg)) if arg == "--secret"));
+    assert!(err.is_empty());
+    assert_eq!(secret.value(), None);
+
+    with_env(&[("CLIUTIL_TEST_SECRET", "hunter2")], || {
+        execute_captured(&app, &[]).result
+    })
+    .unwrap();
+    assert_eq!(secret.value(), Some("hunter2"));
+
+    let help = app.render_help();
+    assert!(help.contains("  --secret <VALUE>  The secret (env: CLIUTIL_TEST_SECRET only)\n"));
+    assert!(!help.contains("[--secret"));
+}
+
+#[test]
+fn parameter_aliases() {
+    let color = ParameterValue::new();
+
+    let parameters = &[Parameter::build()
+        .with_long_name("color")
+        .with_aliases(&["colour"])
+        .with_parameter(&color)
+        .build()];
+
+    let app: Application = Application::build()
+        .with_parameters(parameters)
+        .with_command(&default_command)
+        .build();
+
+    execute_captured(&app, &["--colour", "never"])
+        .result
+        .unwrap();
+    assert_eq!(color.value(), Some("never"));
+
+    execute_captured(&app, &["--colour=always"]).result.unwrap();
+    assert_eq!(color.value(), Some("always"));
+}
+
+#[test]
+fn parameter_optional_value() {
+    fn run(args: &[&str], implicit: Option<&'static str>) -> (Option<String>, bool, Vec<String>) {
+        let color = ParameterValue::new();
+        let positionals = PositionalValue::new();
+
+        let parameter = Parameter::build()
+            .with_long_name("color")
+            .with_optional_value(true)
+            .with_parameter(&color);
+        let parameters = &[match implicit {
+            Some(value) => parameter.with_implicit_value(value).build(),
+            None => parameter.build(),
+        }];
+
+        let app: Application = Application::build()
+            .with_parameters(parameters)
+            .with_positionals(&positionals)
+            .with_command(&default_command)
+            .build();
+
+        execute_captured(&app, args).result.unwrap();
+        (
+            color.value().map(str::to_string),
+            color.is_given(),
+            positionals.values().to_vec(),
+        )
+    }
+
+    assert_eq!(run(&["--color"], None), (None, true, vec![]));
+    assert_eq!(
+        run(&["--color"], Some("auto")),
+        (Some("auto".to_string()), true, vec![])
+    );
+    assert_eq!(
+        run(&["--color=always"], None),
+        (Some("always".to_string()), true, vec![])
+    );
+    // the following argument is never taken as the value
+    assert_eq!(
+        run(&["--color", "next"], None),
+        (None, true, vec!["next".to_string()])
+    );
+    assert_eq!(
+        run(&["--color", "next"], Some("auto")),
+        (Some("auto".to_string()), true, vec!["next".to_string()])
+    );
+    assert_eq!(
+        run(&["next"], None),
+        (None, false, vec!["next".to_string()])
+    );
+}
+
+#[test]
+fn parameter_empty_long_name() {
+    let p = ParameterValue::new();
+
+    let parameters = &[Parameter::build()
+        .with_short_name("p")
+        .with_description("A short parameter")
+        .with_parameter(&p)
+        .build()];
+
+    let app: Application = Application::build()
+        .with_parameters(parameters)
+        .with_command(&default_command)
+        .build();
+
+    for arg in ["--=x", "--="] {
+        let Captured { result, err, .. } = execute_captured(&app, &[arg]);
+        assert!(matches!(result, Err(CommandLineError::UnknownArgument(a)) if a == arg));
+        // nothing to suggest for an empty name
+        assert!(err.is_empty());
+        assert_eq!(p.value(), None);
+    }
+
+    execute_captured(&app, &["-p", "x"]).result.unwrap();
+    assert_eq!(p.value(), Some("x"));
+}
+
+#[test]
+fn parameter_require_equals() {
+    let output = ParameterValue::new();
+
+    let parameters = &[Parameter::build()
+        .with_short_name("o")
+        .with_long_name("output")
+        .with_value_name("FILE")
+        .with_parameter(&output)
+        .build()];
+
+    let app: Application = Application::build()
+        .with_parameters(parameters)
+        .with_command(&default_command)
+        .with_require_equals(true)
+        .build();
+
+    let result = execute_captured(&app, &["--output", "file"]).result;
+    assert!(matches!(
+        &result,
+        Err(CommandLineError::MissingEquals { arg, suggestion })
+            if arg == "--output" && suggestion == "--output=file"
+    ));
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "Expected a value after '=' for --output, e.g. --output=file"
+    );
+    assert_eq!(output.value(), None);
+
+    assert!(matches!(
+        execute_captured(&app, &["--output"]).result,
+        Err(CommandLineError::MissingEquals { suggestion, .. }) if suggestion == "--output=<FILE>"
+    ));
+
+    execute_captured(&app, &["--output=file"]).result.unwrap();
+    assert_eq!(output.value(), Some("file"));
+
+    execute_captured(&app, &["-o", "other"]).result.unwrap();
+    assert_eq!(output.value(), Some("other"));
+}
+
+#[test]
+fn parameter_value_hint() {
+    let output = ParameterValue::new();
+    let host = ParameterValue::new();
+
+    let parameters = &[
+        Parameter::build()
+            .with_long_name("output")
+            .with_parameter(&output)
+            .with_value_hint(ValueHint::FilePath)
+            .build(),
+        Parameter::build()
+            .with_long_name("host")
+            .with_parameter(&host)
+            .build(),
+    ];
+
+    assert_eq!(parameters[0].value_hint(), ValueHint::FilePath);
+    assert_eq!(parameters[1].value_hint(), ValueHint::Other);
+}
+
+#[test]
+fn parameter_value_sources() {
+    let output = ParameterValue::new();
+    let level = ParameterValue::new();
+    let name = ParameterValue::new();
+    let color = ParameterValue::new();
+
+    let parameters = &[
+        Parameter::build()
+            .with_long_name("output-dir")
+            .with_parameter(&output)
+            .build(),
+        Parameter::build()
+            .with_long_name("level")
+            .with_parameter(&level)
+            .build(),
+        Parameter::build()
+            .with_long_name("name")
+            .with_parameter(&name)
+            .build(),
+        Parameter::build()
+            .with_long_name("color")
+            .with_parameter(&color)
+            .build(),
+    ];
+
+    static SOURCES: &[&(dyn ValueSource + Sync)] = &[
+        &EnvSource::new("CLIUTIL_SOURCES"),
+        &DefaultSource::new(&[("output-dir", "default"), ("name", "ferris")]),
+        &|name: &str| (name == "name" || name == "color").then(|| "closure".to_string()),
+    ];
+
+    let app: Application = Application::build()
+        .with_parameters(parameters)
+        .with_command(&default_command)
+        .with_value_sources(SOURCES)
+        .with_config_source(|_| Some("configured".to_string()))
+        .build();
+
+    let vars = [
+        ("CLIUTIL_SOURCES_OUTPUT_DIR", "from-env"),
+        ("CLIUTIL_SOURCES_LEVEL", "2"),
+    ];
+    with_env(&vars, || execute_captured(&app, &["--level", "1"]).result).unwrap();
+
+    // the command line wins, then the sources in order, then the config source
+    assert_eq!(level.value(), Some("1"));
+    assert_eq!(output.value(), Some("from-env"));
+    assert_eq!(name.value(), Some("ferris"));
+    assert_eq!(color.value(), Some("closure"));
+}
+
+#[test]
+fn parameter_env_source_var_name() {
+    assert_eq!(
+        EnvSource::new("APP").var_name("output-dir"),
+        "APP_OUTPUT_DIR"
+    );
+    assert_eq!(EnvSource::new("").var_name("level"), "LEVEL");
+}
@@ -0,0 +1,173 @@
+use super::*;
+
+#[test]
+fn positional_parsing_1() {
+    let f = FlagValue::new();
+    let positionals = PositionalValue::new();
+
+    let flags = &[Flag::build()
+        .with_short_name("f")
+        .with_description("A flag")
+        .with_flag(&f)
+        .build()];
+
+    let app: Application = Application::build()
+        .with_flags(flags)
+        .with_positionals(&positionals)
+        .with_command(&default_command)
+        .build();
+
+    let args = &["first", "-f", "second"];
+
+    app.execute(args.iter()).unwrap();
+
+    assert!(f.value());
+    assert_eq!(positionals.values(), ["first", "second"]);
+}
+
+#[test]
+fn positional_negative_number() {
+    let positionals = PositionalValue::new();
+
+    let app: Application = Application::build()
+        .with_positionals(&positionals)
+        .with_command(&default_command)
+        .build();
+
+    let args = &["-5", "-1.5"];
+
+    app.execute(args.iter()).unwrap();
+
+    assert_eq!(positionals.values(), ["-5", "-1.5"]);
+}
+
+#[test]
+fn positional_unexpected() {
+    let app: Application = Application::build().with_command(&default_command).build();
+
+    let args = &["-5"];
+
+    assert!(matches!(
+        app.execute(args.iter()),
+        Err(CommandLineError::UnknownCommand(_))
+    ));
+}
+
+#[test]
+fn number_detection() {
+    assert!(is_number("5"));
+    assert!(is_number("123.45"));
+    assert!(!is_number(""));
+    assert!(!is_number("5."));
+    assert!(!is_number(".5"));
+    assert!(!is_number("f"));
+    assert!(!"-5".is_short_name());
+    assert!("-f".is_short_name());
+}
+
+#[test]
+fn positional_stdin() {
+    let f = FlagValue::new();
+    let positionals = PositionalValue::new();
+
+    let flags = &[Flag::build()
+        .with_long_name("flag")
+        .with_description("A flag without a short name")
+        .with_flag(&f)
+        .build()];
+
+    let app: Application = Application::build()
+        .with_flags(flags)
+        .with_positionals(&positionals)
+        .with_command(&default_command)
+        .build();
+
+    let args = &["-"];
+
+    app.execute(args.iter()).unwrap();
+
+    assert!(!f.value());
+    assert_eq!(positionals.values(), ["-"]);
+}
+
+#[test]
+fn positional_streaming() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const COUNT: usize = 200_000;
+    static PRODUCED: AtomicUsize = AtomicUsize::new(0);
+    static HANDLED: AtomicUsize = AtomicUsize::new(0);
+
+    fn handle(value: &str) {
+        let handled = HANDLED.fetch_add(1, Ordering::SeqCst);
+        // each argument is handled as soon as it is read, nothing more is pulled ahead of it
+        assert_eq!(PRODUCED.load(Ordering::SeqCst), handled + 1);
+        assert_eq!(value, handled.to_string());
+    }
+
+    let positionals = PositionalValue::streaming(handle);
+
+    let app: Application = Application::build()
+        .with_positionals(&positionals)
+        .with_command(&default_command)
+        .build();
+
+    let args = (0..COUNT).map(|index| {
+        PRODUCED.fetch_add(1, Ordering::SeqCst);
+        index.to_string()
+    });
+
+    app.execute(args).unwrap();
+
+    assert_eq!(HANDLED.load(Ordering::SeqCst), COUNT);
+    assert!(positionals.values().is_empty());
+}
+
+#[test]
+fn positional_unknown_subcommand() {
+    fn run(
+        args: &[&str],
+        enabled: bool,
+    ) -> (Result<RunOutcome<()>, CommandLineError>, Vec<String>) {
+        let positionals = PositionalValue::new();
+
+        let subcommands = &[SubCommand::build()
+            .with_long_name("build")
+            .with_command(&default_command)
+            .build()];
+
+        let app: Application = Application::build()
+            .with_subcommands(subcommands)
+            .with_positionals(&positionals)
+            .with_command(&default_command)
+            .with_unknown_as_positional(enabled)
+            .build();
+
+        let result = execute_captured(&app, args).result;
+        (result, positionals.values().to_vec())
+    }
+
+    // an unknown command is a positional either way
+    for enabled in [true, false] {
+        let (result, positionals) = run(&["foo", "bar"], enabled);
+        assert!(matches!(result, Ok(RunOutcome::Completed(()))));
+        assert_eq!(positionals, ["foo", "bar"]);
+    }
+
+    // enabled, everything after it is passed on as is
+    let (result, positionals) = run(&["foo", "--bar", "build"], true);
+    assert!(matches!(result, Ok(RunOutcome::Completed(()))));
+    assert_eq!(positionals, ["foo", "--bar", "build"]);
+
+    let (result, positionals) = run(&["foo", "build"], true);
+    assert!(matches!(result, Ok(RunOutcome::Completed(()))));
+    assert_eq!(positionals, ["foo", "build"]);
+
+    // disabled, the rest is still parsed: options are checked and subcommands selected
+    let (result, _) = run(&["foo", "--bar"], false);
+    assert!(matches!(result, Err(CommandLineError::UnknownArgument(arg)) if arg == "--bar"));
+
+    let (result, positionals) = run(&["foo", "build"], false);
+    assert!(matches!(result, Ok(RunOutcome::Completed(()))));
+    assert_eq!(positionals, ["foo"]);
+}
@@ -0,0 +1,718 @@
+use super::*;
+
+static REMOTE_SUBCOMMANDS: &[SubCommand] = &[
+    SubCommand::build()
+        .with_long_name("add")
+        .with_description("Adds a remote")
+        .with_command(&default_command)
+        .build(),
+    SubCommand::build()
+        .with_long_name("remove")
+        .with_description("Removes a remote")
+        .with_command(&default_command)
+        .build(),
+];
+
+#[test]
+fn subcommand_list_on_missing() {
+    let subcommands = &[SubCommand::build()
+        .with_long_name("remote")
+        .with_description("Manages remotes")
+        .with_subcommands(REMOTE_SUBCOMMANDS)
+        .with_list_on_missing(true)
+        .build()];
+
+    let app: Application = Application::build().with_subcommands(subcommands).build();
+
+    let Captured { result, out, .. } = execute_captured(&app, &["remote"]);
+
+    assert!(matches!(result, Ok(RunOutcome::HelpShown)));
+    assert!(out.starts_with("remote\nManages remotes\n"));
+    assert!(out.contains("Subcommands:\n  add"));
+    assert!(out.contains("  remove"));
+}
+
+#[test]
+fn subcommand_expected_on_missing() {
+    let subcommands = &[SubCommand::build()
+        .with_long_name("remote")
+        .with_description("Manages remotes")
+        .with_subcommands(REMOTE_SUBCOMMANDS)
+        .build()];
+
+    let app: Application = Application::build().with_subcommands(subcommands).build();
+
+    let Captured { result, out, .. } = execute_captured(&app, &["remote"]);
+
+    assert!(matches!(result, Err(CommandLineError::ExpectedSubcommand)));
+    assert!(out.is_empty());
+}
+
+#[test]
+fn subcommand_empty_args_help() {
+    let app: Application = Application::build()
+        .with_name("remote")
+        .with_subcommands(REMOTE_SUBCOMMANDS)
+        .with_empty_args_help(true)
+        .build();
+
+    let Captured { result, out, .. } = execute_captured(&app, &[]);
+
+    assert!(matches!(result, Ok(RunOutcome::HelpShown)));
+    assert!(out.starts_with("remote\n"));
+    assert!(out.contains("Subcommands:\n  add"));
+
+    let Captured { result, .. } = execute_captured(&app, &["--unknown"]);
+    assert!(matches!(result, Err(CommandLineError::UnknownArgument(_))));
+}
+
+#[test]
+fn subcommand_empty_args_expected() {
+    let app: Application = Application::build()
+        .with_subcommands(REMOTE_SUBCOMMANDS)
+        .build();
+
+    let Captured { result, out, .. } = execute_captured(&app, &[]);
+
+    assert!(matches!(result, Err(CommandLineError::ExpectedSubcommand)));
+    assert!(out.is_empty());
+}
+
+#[test]
+fn subcommand_aliases() {
+    fn checkout() -> &'static str {
+        "checkout"
+    }
+
+    fn commit() -> &'static str {
+        "commit"
+    }
+
+    let subcommands: &[SubCommand<&str>] = &[
+        SubCommand::build()
+            .with_long_name("checkout")
+            .with_aliases(&["co"])
+            .with_description("Switches branches")
+            .with_command(&checkout)
+            .build(),
+        SubCommand::build()
+            .with_long_name("commit")
+            .with_aliases(&["ci", "cm"])
+            .with_description("Records changes")
+            .with_command(&commit)
+            .build(),
+    ];
+
+    let app: Application<&str> = Application::build().with_subcommands(subcommands).build();
+
+    assert!(matches!(
+        execute_captured(&app, &["co"]).result,
+        Ok(RunOutcome::Completed("checkout"))
+    ));
+    assert!(matches!(
+        execute_captured(&app, &["cm"]).result,
+        Ok(RunOutcome::Completed("commit"))
+    ));
+    assert!(matches!(
+        execute_captured(&app, &["CO"]).result,
+        Err(CommandLineError::UnknownCommand(_))
+    ));
+
+    let help = capture_help(&app, &app);
+    assert!(help.contains("  checkout, co      Switches branches\n"));
+    assert!(help.contains("  commit, ci, cm    Records changes\n"));
+}
+
+#[test]
+fn subcommand_aliases_case_insensitive() {
+    fn checkout() -> &'static str {
+        "checkout"
+    }
+
+    let subcommands: &[SubCommand<&str>] = &[SubCommand::build()
+        .with_long_name("checkout")
+        .with_aliases(&["co"])
+        .with_description("Switches branches")
+        .with_command(&checkout)
+        .build()];
+
+    let app: Application<&str> = Application::build()
+        .with_subcommands(subcommands)
+        .with_case_insensitive(true)
+        .build();
+
+    for name in ["CO", "co", "Co", "CHECKOUT", "checkout"] {
+        assert!(
+            matches!(
+                execute_captured(&app, &[name]).result,
+                Ok(RunOutcome::Completed("checkout"))
+            ),
+            "{}",
+            name
+        );
+    }
+}
+
+/// A chain of `depth` subcommands named `sub`, each owning the next.
+fn nested_subcommands(depth: usize) -> SubCommand<'static> {
+    let mut command = SubCommand::build()
+        .with_long_name("sub")
+        .with_command(&default_command)
+        .build();
+    for _ in 1..depth {
+        command = SubCommand::build()
+            .with_long_name("sub")
+            .with_owned_subcommands(vec![command])
+            .build_owned();
+    }
+    command
+}
+
+#[test]
+fn subcommand_max_depth() {
+    const DEPTH: usize = 70;
+    let args = ["sub"; DEPTH];
+
+    let app: Application = Application::build()
+        .with_owned_subcommands(vec![nested_subcommands(DEPTH)])
+        .build_owned();
+    assert!(matches!(
+        execute_captured(&app, &args).result,
+        Err(CommandLineError::MaxDepthExceeded)
+    ));
+
+    let app: Application = Application::build()
+        .with_owned_subcommands(vec![nested_subcommands(DEPTH)])
+        .with_max_depth(DEPTH)
+        .build_owned();
+    assert!(execute_captured(&app, &args).result.is_ok());
+}
+
+/// Subcommands that are their own subcommands.
+struct Cycle<const N: usize>([SubCommand<'static>; N]);
+
+static CYCLE: Cycle<1> = Cycle([SubCommand::build()
+    .with_long_name("again")
+    .with_subcommands(&CYCLE.0)
+    .with_command(&default_command)
+    .build()]);
+
+static CYCLE_FLAG: FlagValue = FlagValue::new();
+
+static CYCLE_FLAGS: &[Flag] = &[Flag::build()
+    .with_short_name("v")
+    .with_flag(&CYCLE_FLAG)
+    .build()];
+
+static BRANCHING_CYCLE: Cycle<2> = Cycle([
+    SubCommand::build()
+        .with_long_name("left")
+        .with_flags(CYCLE_FLAGS)
+        .with_subcommands(&BRANCHING_CYCLE.0)
+        .with_command(&default_command)
+        .build(),
+    SubCommand::build()
+        .with_long_name("right")
+        .with_subcommands(&BRANCHING_CYCLE.0)
+        .with_command(&default_command)
+        .build(),
+]);
+
+#[test]
+fn subcommand_cycle() {
+    let app: Application = Application::build()
+        .with_subcommands(&CYCLE.0)
+        .with_indexed_lookup(true)
+        .with_max_depth(8)
+        .build();
+
+    assert!(execute_captured(&app, &["again"; 8]).result.is_ok());
+    assert!(matches!(
+        execute_captured(&app, &["again"; 9]).result,
+        Err(CommandLineError::MaxDepthExceeded)
+    ));
+}
+
+#[test]
+fn subcommand_branching_cycle_indexed() {
+    // every path down to the default depth of 64 would be walked if lists were indexed once per path
+    let app: Application = Application::build()
+        .with_subcommands(&BRANCHING_CYCLE.0)
+        .with_indexed_lookup(true)
+        .build();
+
+    execute_captured(&app, &["right", "left", "right", "left", "-v"])
+        .result
+        .unwrap();
+    assert!(CYCLE_FLAG.value());
+}
+
+#[test]
+fn subcommand_flag() {
+    fn deploy() -> &'static str {
+        "deploy"
+    }
+
+    let subcommands: &[SubCommand<&str>] = &[SubCommand::build()
+        .with_long_name("deploy")
+        .with_description("Deploys the application")
+        .with_command(&deploy)
+        .build()];
+
+    let app: Application<&str> = Application::build()
+        .with_subcommands(subcommands)
+        .with_subcommand_flag("command")
+        .build();
+
+    assert!(matches!(
+        execute_captured(&app, &["--command=deploy"]).result,
+        Ok(RunOutcome::Completed("deploy"))
+    ));
+    assert!(matches!(
+        execute_captured(&app, &["deploy"]).result,
+        Ok(RunOutcome::Completed("deploy"))
+    ));
+    assert!(matches!(
+        execute_captured(&app, &["--command=unknown"]).result,
+        Err(CommandLineError::UnknownCommand(command)) if command == "unknown"
+    ));
+}
+
+#[test]
+fn subcommand_execute_tracked() {
+    let subcommands = &[SubCommand::build()
+        .with_long_name("remote")
+        .with_description("Manages remotes")
+        .with_subcommands(REMOTE_SUBCOMMANDS)
+        .build()];
+
+    let app: Application = Application::build()
+        .with_subcommands(subcommands)
+        .with_command(&default_command)
+        .build();
+
+    let ((), path) = app.execute_tracked(["remote", "add"].iter()).unwrap();
+    assert_eq!(path, ["remote", "add"]);
+
+    let ((), path) = app.execute_tracked(std::iter::empty::<&str>()).unwrap();
+    assert!(path.is_empty());
+}
+
+#[test]
+fn subcommand_help_flag() {
+    let deploy_flag = FlagValue::new();
+    let deploy_flags = &[Flag::build()
+        .with_long_name("force")
+        .with_description("Deploys even if checks fail")
+        .with_flag(&deploy_flag)
+        .build()];
+    let subcommands = &[SubCommand::build()
+        .with_long_name("deploy")
+        .with_description("Deploys the project")
+        .with_flags(deploy_flags)
+        .with_command(&default_command)
+        .build()];
+
+    let app: Application = Application::build()
+        .with_name("app")
+        .with_subcommands(subcommands)
+        .with_command(&default_command)
+        .build();
+
+    for args in [
+        &["deploy", "--help"][..],
+        &["deploy", "-h"],
+        &["deploy", "help"],
+    ] {
+        let Captured { result, out, .. } = execute_captured(&app, args);
+        assert!(matches!(result, Ok(RunOutcome::HelpShown)));
+        assert_eq!(out, subcommands[0].render_help());
+        assert!(out.contains("--force"));
+    }
+
+    let Captured { result, out, .. } = execute_captured(&app, &["--help"]);
+    assert!(matches!(result, Ok(RunOutcome::HelpShown)));
+    assert_eq!(out, app.render_help());
+    assert!(!out.contains("--force"));
+}
+
+#[test]
+fn subcommand_required_listing() {
+    let subcommands = &[
+        SubCommand::build()
+            .with_long_name("deploy")
+            .with_description("Deploys the project")
+            .with_command(&default_command)
+            .build(),
+        SubCommand::build()
+            .with_long_name("status")
+            .with_description("Shows the status")
+            .with_command(&default_command)
+            .build(),
+    ];
+
+    let app: Application = Application::build()
+        .with_subcommands(subcommands)
+        .with_require_subcommand(true)
+        .build();
+
+    let Captured { result, out, err } = execute_captured(&app, &[]);
+    assert!(matches!(result, Err(CommandLineError::ExpectedSubcommand)));
+    assert!(out.is_empty());
+    assert_eq!(
+        err,
+        "Subcommands:\n  deploy            Deploys the project\n  status            Shows the status\n\n"
+    );
+
+    let app: Application = Application::build().with_subcommands(subcommands).build();
+
+    let Captured { result, err, .. } = execute_captured(&app, &[]);
+    assert!(matches!(result, Err(CommandLineError::ExpectedSubcommand)));
+    assert!(err.is_empty());
+}
+
+#[test]
+fn subcommand_write_subcommands() {
+    let verbose = FlagValue::new();
+    let flags = &[Flag::build()
+        .with_long_name("verbose")
+        .with_flag(&verbose)
+        .build()];
+    let subcommands = &[
+        SubCommand::build()
+            .with_long_name("deploy")
+            .with_description("Deploys the project")
+            .with_command(&default_command)
+            .build(),
+        SubCommand::build()
+            .with_long_name("log")
+            .with_description("Shows the history")
+            .with_category("Examine history")
+            .with_command(&default_command)
+            .build(),
+    ];
+
+    let app: Application = Application::build()
+        .with_name("app")
+        .with_description("An application")
+        .with_flags(flags)
+        .with_subcommands(subcommands)
+        .build();
+
+    let mut out = Vec::new();
+    app.write_subcommands(&mut out).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert_eq!(
+        out,
+        "Subcommands:\n  deploy            Deploys the project\n\n\
+         Examine history:\n  log               Shows the history\n\n"
+    );
+    assert!(!out.contains("Flags:"));
+}
+
+#[test]
+fn subcommand_global_parameter() {
+    for args in [
+        &["--config", "c", "sub"][..],
+        &["sub", "--config", "c"],
+        &["sub", "-c", "c"],
+    ] {
+        let config = ParameterValue::new();
+        let params = &[Parameter::build()
+            .with_short_name("c")
+            .with_long_name("config")
+            .with_parameter(&config)
+            .with_global(true)
+            .build()];
+        let subcommands = &[SubCommand::build()
+            .with_long_name("sub")
+            .with_command(&default_command)
+            .build()];
+        let app: Application = Application::build()
+            .with_parameters(params)
+            .with_subcommands(subcommands)
+            .build();
+
+        let Captured { result, .. } = execute_captured(&app, args);
+        assert!(result.is_ok(), "{:?}", args);
+        assert_eq!(config.value(), Some("c"), "{:?}", args);
+    }
+}
+
+#[test]
+fn subcommand_global_parameter_required() {
+    let config = ParameterValue::new();
+    let local = ParameterValue::new();
+    let params = &[
+        Parameter::build()
+            .with_long_name("config")
+            .with_parameter(&config)
+            .with_required(true)
+            .with_global(true)
+            .build(),
+        Parameter::build()
+            .with_long_name("local")
+            .with_parameter(&local)
+            .build(),
+    ];
+    let subcommands = &[SubCommand::build()
+        .with_long_name("sub")
+        .with_command(&default_command)
+        .build()];
+    let app: Application = Application::build()
+        .with_parameters(params)
+        .with_subcommands(subcommands)
+        .build();
+
+    let Captured { result, .. } = execute_captured(&app, &["sub"]);
+    assert!(matches!(
+        result,
+        Err(CommandLineError::MissingRequiredParameter(name)) if name == "--config"
+    ));
+
+    let Captured { result, .. } = execute_captured(&app, &["sub", "--config", "c"]);
+    assert!(result.is_ok());
+
+    // only global parameters are inherited
+    let Captured { result, .. } = execute_captured(&app, &["--config", "c", "sub", "--local", "l"]);
+    assert!(matches!(result, Err(CommandLineError::UnknownArgument(_))));
+}
+
+#[test]
+fn subcommand_default_handler() {
+    static CALLS: std::sync::Mutex<Vec<(String, Vec<String>)>> = std::sync::Mutex::new(Vec::new());
+
+    fn external(name: &str, args: &[String]) {
+        CALLS
+            .lock()
+            .unwrap()
+            .push((name.to_string(), args.to_vec()));
+    }
+
+    let verbose = FlagValue::new();
+    let flags = &[Flag::build()
+        .with_long_name("verbose")
+        .with_flag(&verbose)
+        .build()];
+    let subcommands = &[SubCommand::build()
+        .with_long_name("deploy")
+        .with_command(&default_command)
+        .build()];
+    let app: Application = Application::build()
+        .with_flags(flags)
+        .with_subcommands(subcommands)
+        .with_default_subcommand(external)
+        .build();
+
+    let Captured { result, .. } = execute_captured(&app, &["--verbose", "foo", "--bar", "baz"]);
+    assert!(matches!(result, Ok(RunOutcome::Completed(()))));
+    assert!(verbose.value());
+    assert_eq!(
+        *CALLS.lock().unwrap(),
+        [(
+            "foo".to_string(),
+            vec!["--bar".to_string(), "baz".to_string()]
+        )]
+    );
+
+    let Captured { result, .. } = execute_captured(&app, &["deploy"]);
+    assert!(result.is_ok());
+    assert_eq!(CALLS.lock().unwrap().len(), 1);
+
+    let app: Application = Application::build().with_subcommands(subcommands).build();
+
+    let Captured { result, .. } = execute_captured(&app, &["foo"]);
+    assert!(matches!(result, Err(CommandLineError::UnknownCommand(name)) if name == "foo"));
+}
+
+#[test]
+fn subcommand_short_name() {
+    fn build() -> &'static str {
+        "build"
+    }
+
+    let subcommands: &[SubCommand<&str>] = &[SubCommand::build()
+        .with_short_name("b")
+        .with_long_name("build")
+        .with_aliases(&["make"])
+        .with_description("Builds the project")
+        .with_command(&build)
+        .build()];
+
+    let app: Application<&str> = Application::build().with_subcommands(subcommands).build();
+
+    for name in ["b", "build", "make"] {
+        assert!(matches!(
+            execute_captured(&app, &[name]).result,
+            Ok(RunOutcome::Completed("build"))
+        ));
+    }
+
+    let help = capture_help(&app, &app);
+    assert!(help.contains("  b, build, make    Builds the project\n"));
+
+    let clashing: &[SubCommand<&str>] = &[
+        SubCommand::build()
+            .with_short_name("b")
+            .with_long_name("build")
+            .with_command(&build)
+            .build(),
+        SubCommand::build()
+            .with_long_name("bench")
+            .with_aliases(&["b"])
+            .with_command(&build)
+            .build(),
+    ];
+    let result = Application::<&str>::build()
+        .with_subcommands(clashing)
+        .try_build();
+    assert!(matches!(result, Err(BuildError::DuplicateName(name)) if name == "b"));
+}
+
+#[test]
+fn subcommand_end_of_options() {
+    let root_flag = FlagValue::new();
+    let sub_flag = FlagValue::new();
+    let root_args = PositionalValue::new();
+    let sub_args = PositionalValue::new();
+
+    let root_flags = &[Flag::build()
+        .with_short_name("x")
+        .with_flag(&root_flag)
+        .build()];
+    let sub_flags = &[Flag::build()
+        .with_short_name("x")
+        .with_flag(&sub_flag)
+        .build()];
+    let subcommands = &[SubCommand::build()
+        .with_long_name("sub")
+        .with_flags(sub_flags)
+        .with_positionals(&sub_args)
+        .with_command(&default_command)
+        .build()];
+    let app: Application = Application::build()
+        .with_flags(root_flags)
+        .with_positionals(&root_args)
+        .with_subcommands(subcommands)
+        .with_command(&default_command)
+        .build();
+
+    // `--` ends the options of the root only: `sub` is still dispatched and parses its own options
+    execute_captured(&app, &["--", "sub", "-x"]).result.unwrap();
+    assert!(sub_flag.value());
+    assert!(!root_flag.value());
+    assert!(root_args.values().is_empty());
+    assert!(sub_args.values().is_empty());
+
+    // `--` inside the subcommand ends its options, so `-x` is one of its positionals
+    sub_flag.set(false);
+    execute_captured(&app, &["sub", "--", "-x"]).result.unwrap();
+    assert!(!sub_flag.value());
+    assert_eq!(sub_args.values(), ["-x"]);
+
+    // only the first `--` is consumed
+    execute_captured(&app, &["--", "-x", "--"]).result.unwrap();
+    assert!(!root_flag.value());
+    assert_eq!(root_args.values(), ["-x", "--"]);
+
+    // `help` after `--` is a positional, not the help command
+    let Captured { result, out, .. } = execute_captured(&app, &["--", "help"]);
+    assert!(matches!(result, Ok(RunOutcome::Completed(()))));
+    assert!(out.is_empty());
+    assert_eq!(root_args.values(), ["-x", "--", "help"]);
+}
+
+#[test]
+fn subcommand_version() {
+    let subcommands = &[
+        SubCommand::build()
+            .with_long_name("plugin")
+            .with_version("0.3.1")
+            .with_command(&default_command)
+            .build(),
+        SubCommand::build()
+            .with_long_name("build")
+            .with_command(&default_command)
+            .build(),
+    ];
+
+    let app: Application = Application::build()
+        .with_name("myapp")
+        .with_version("1.2.0")
+        .with_subcommands(subcommands)
+        .with_command(&default_command)
+        .build();
+
+    for (args, expected) in [
+        (&["--version"][..], "myapp 1.2.0\n"),
+        (&["plugin", "--version"], "plugin 0.3.1\n"),
+        (&["build", "--version"], "build 1.2.0\n"),
+    ] {
+        let Captured { result, out, .. } = execute_captured(&app, args);
+        assert!(matches!(result, Ok(RunOutcome::VersionShown)));
+        assert_eq!(out, expected);
+    }
+
+    // without a version, --version is an unknown argument
+    let app: Application = Application::build().with_subcommands(subcommands).build();
+    assert!(matches!(
+        execute_captured(&app, &["--version"]).result,
+        Err(CommandLineError::UnknownArgument(arg)) if arg == "--version"
+    ));
+    assert!(matches!(
+        execute_captured(&app, &["plugin", "--version"]).result,
+        Ok(RunOutcome::VersionShown)
+    ));
+}
+
+#[test]
+fn subcommand_excluded_globals() {
+    let color = ParameterValue::new();
+    let params = &[Parameter::build()
+        .with_long_name("color")
+        .with_parameter(&color)
+        .with_global(true)
+        .build()];
+    let nested = &[SubCommand::build()
+        .with_long_name("csv")
+        .with_command(&default_command)
+        .build()];
+    let subcommands = &[
+        SubCommand::build()
+            .with_long_name("show")
+            .with_command(&default_command)
+            .build(),
+        SubCommand::build()
+            .with_long_name("export")
+            .with_excluded_globals(&["color"])
+            .with_subcommands(nested)
+            .with_command(&default_command)
+            .build(),
+    ];
+    let app: Application = Application::build()
+        .with_parameters(params)
+        .with_subcommands(subcommands)
+        .build();
+
+    execute_captured(&app, &["show", "--color", "always"])
+        .result
+        .unwrap();
+    assert_eq!(color.value(), Some("always"));
+
+    for args in [
+        &["export", "--color", "never"][..],
+        &["export", "csv", "--color", "never"],
+    ] {
+        assert!(matches!(
+            execute_captured(&app, args).result,
+            Err(CommandLineError::UnknownArgument(arg)) if arg == "--color"
+        ));
+    }
+    assert_eq!(color.value(), Some("always"));
+
+    // still accepted before the subcommand
+    execute_captured(&app, &["--color", "auto", "export"])
+        .result
+        .unwrap();
+    assert_eq!(color.value(), Some("auto"));
+}
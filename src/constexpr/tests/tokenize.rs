@@ -0,0 +1,80 @@
+use super::*;
+use crate::constexpr::tokenize::tokenize;
+
+#[test]
+fn tokenize_whitespace() {
+    assert_eq!(
+        tokenize("  -f   --param value ").unwrap(),
+        ["-f", "--param", "value"]
+    );
+    assert!(tokenize("").unwrap().is_empty());
+}
+
+#[test]
+fn tokenize_quotes() {
+    assert_eq!(
+        tokenize("--path \"a b\" -f").unwrap(),
+        ["--path", "a b", "-f"]
+    );
+    assert_eq!(tokenize("'a \"b\" c'").unwrap(), ["a \"b\" c"]);
+    assert_eq!(tokenize("--name='x y'z").unwrap(), ["--name=x yz"]);
+    assert_eq!(tokenize("'' \"\"").unwrap(), ["", ""]);
+}
+
+#[test]
+fn tokenize_escapes() {
+    assert_eq!(tokenize(r"a\ b c").unwrap(), ["a b", "c"]);
+    assert_eq!(tokenize(r#""say \"hi\"""#).unwrap(), [r#"say "hi""#]);
+    assert_eq!(tokenize(r#""C:\dir\\""#).unwrap(), [r"C:\dir\"]);
+    assert_eq!(tokenize(r"'\n'").unwrap(), [r"\n"]);
+}
+
+#[test]
+fn tokenize_unterminated() {
+    assert!(matches!(
+        tokenize("--path \"a b"),
+        Err(CommandLineError::ParseError(message)) if message == "Unterminated \" quote"
+    ));
+    assert!(matches!(
+        tokenize("'a"),
+        Err(CommandLineError::ParseError(message)) if message == "Unterminated ' quote"
+    ));
+    assert!(matches!(
+        tokenize(r"a\"),
+        Err(CommandLineError::ParseError(_))
+    ));
+}
+
+#[test]
+fn execute_str() {
+    let f = FlagValue::new();
+    let p = ParameterValue::new();
+
+    let flags = &[Flag::build()
+        .with_short_name("f")
+        .with_description("A flag")
+        .with_flag(&f)
+        .build()];
+
+    let parameters = &[Parameter::build()
+        .with_long_name("path")
+        .with_description("A path")
+        .with_parameter(&p)
+        .build()];
+
+    let app: Application = Application::build()
+        .with_flags(flags)
+        .with_parameters(parameters)
+        .with_command(&default_command)
+        .build();
+
+    app.execute_str("--path \"a b\" -f").unwrap();
+
+    assert_eq!(p.value(), Some("a b"));
+    assert!(f.value());
+
+    assert!(matches!(
+        app.execute_str("--path 'a b"),
+        Err(CommandLineError::ParseError(_))
+    ));
+}
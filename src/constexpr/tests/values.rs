@@ -0,0 +1,248 @@
+use super::*;
+
+#[test]
+fn collect_values() {
+    let f = FlagValue::new();
+    let g = FlagValue::new();
+    let p = ParameterValue::new();
+    let q = ParameterValue::new();
+    let positionals = PositionalValue::new();
+
+    let flags = &[
+        Flag::build()
+            .with_long_name("flag")
+            .with_description("A flag")
+            .with_flag(&f)
+            .build(),
+        Flag::build()
+            .with_short_name("g")
+            .with_description("A short flag")
+            .with_flag(&g)
+            .build(),
+    ];
+
+    let parameters = &[
+        Parameter::build()
+            .with_long_name("param")
+            .with_description("A parameter")
+            .with_parameter(&p)
+            .build(),
+        Parameter::build()
+            .with_long_name("unset")
+            .with_description("An unset parameter")
+            .with_parameter(&q)
+            .build(),
+    ];
+
+    let app: Application = Application::build()
+        .with_flags(flags)
+        .with_parameters(parameters)
+        .with_positionals(&positionals)
+        .with_command(&default_command)
+        .build();
+
+    let result = execute_captured(&app, &["--flag", "--param", "value", "a", "b"]).result;
+    assert!(matches!(result, Ok(RunOutcome::Completed(()))));
+
+    let values = app.collect_values();
+
+    assert_eq!(values.len(), 5);
+    assert_eq!(values["flag"], ArgValue::Flag(true));
+    assert_eq!(values["g"], ArgValue::Flag(false));
+    assert_eq!(values["param"], ArgValue::Param(Some("value".to_string())));
+    assert_eq!(values["unset"], ArgValue::Param(None));
+    assert_eq!(
+        values[POSITIONALS_KEY],
+        ArgValue::Multi(vec!["a".to_string(), "b".to_string()])
+    );
+}
+
+#[test]
+fn config_dump_flag() {
+    static RAN: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+    fn deploy() {
+        RAN.store(true, std::sync::atomic::Ordering::SeqCst)
+    }
+
+    let verbose = FlagValue::new();
+    let output = ParameterValue::new();
+    let level = ParameterValue::new();
+    let force = FlagValue::new();
+
+    let flags = &[Flag::build()
+        .with_short_name("v")
+        .with_long_name("verbose")
+        .with_description("Prints more")
+        .with_flag(&verbose)
+        .build()];
+    let parameters = &[
+        Parameter::build()
+            .with_long_name("output")
+            .with_description("The output file")
+            .with_parameter(&output)
+            .build(),
+        Parameter::build()
+            .with_short_name("l")
+            .with_description("The level")
+            .with_parameter(&level)
+            .build(),
+    ];
+    let deploy_flags = &[Flag::build()
+        .with_long_name("force")
+        .with_description("Deploys anyway")
+        .with_flag(&force)
+        .build()];
+    let subcommands = &[SubCommand::build()
+        .with_long_name("deploy")
+        .with_description("Deploys the project")
+        .with_flags(deploy_flags)
+        .with_command(&deploy)
+        .build()];
+
+    let app: Application = Application::build()
+        .with_flags(flags)
+        .with_parameters(parameters)
+        .with_subcommands(subcommands)
+        .with_command(&default_command)
+        .with_config_source(|name| (name == "output").then(|| "configured.txt".to_string()))
+        .with_config_dump_flag("print-config")
+        .build();
+
+    let Captured { result, out, .. } =
+        execute_captured(&app, &["deploy", "--force", "--print-config"]);
+    assert!(matches!(result, Ok(RunOutcome::ConfigPrinted)));
+    assert_eq!(
+        out,
+        "--verbose: false\n--output: configured.txt\n-l: <unset>\n--force: true\n"
+    );
+    assert!(!RAN.load(std::sync::atomic::Ordering::SeqCst));
+}
+
+#[test]
+fn config_dump_flag_disabled() {
+    let app: Application = Application::build().with_command(&default_command).build();
+
+    assert!(matches!(
+        execute_captured(&app, &["--print-config"]).result,
+        Err(CommandLineError::UnknownArgument(arg)) if arg == "--print-config"
+    ));
+}
+
+#[test]
+fn debug_state() {
+    let test = FlagValue::new();
+    let quiet = FlagValue::new();
+    let param = ParameterValue::new();
+    let tags = MultiParameterValue::new();
+
+    let flags = &[
+        Flag::build()
+            .with_short_name("t")
+            .with_long_name("test")
+            .with_flag(&test)
+            .build(),
+        Flag::build().with_short_name("q").with_flag(&quiet).build(),
+    ];
+    let params = &[
+        Parameter::build()
+            .with_short_name("p")
+            .with_long_name("param")
+            .with_parameter(&param)
+            .build(),
+        Parameter::build()
+            .with_long_name("tag")
+            .with_multi_parameter(&tags)
+            .build(),
+    ];
+
+    let app: Application = Application::build()
+        .with_name("Example App")
+        .with_flags(flags)
+        .with_parameters(params)
+        .with_command(&default_command)
+        .build();
+
+    assert_eq!(
+        app.debug_state(),
+        "test: false\nq: false\nparam: None\ntag: []\n"
+    );
+
+    execute_captured(&app, &["-t", "-p", "value", "--tag", "a", "--tag", "b"])
+        .result
+        .unwrap();
+    assert_eq!(
+        app.debug_state(),
+        "test: true\nq: false\nparam: value\ntag: [a, b]\n"
+    );
+}
+
+#[test]
+fn binder() {
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    static BOUND: Mutex<Vec<(String, ArgValue)>> = Mutex::new(Vec::new());
+
+    fn bind(name: &str, value: ArgValue) {
+        BOUND.lock().unwrap().push((name.to_string(), value));
+    }
+
+    let verbose = FlagValue::new();
+    let output = ParameterValue::new();
+    let force = FlagValue::new();
+    let other = FlagValue::new();
+
+    let flags = &[Flag::build()
+        .with_long_name("verbose")
+        .with_flag(&verbose)
+        .build()];
+    let parameters = &[Parameter::build()
+        .with_long_name("output")
+        .with_parameter(&output)
+        .build()];
+    let build_flags = &[Flag::build().with_short_name("f").with_flag(&force).build()];
+    let other_flags = &[Flag::build()
+        .with_long_name("other")
+        .with_flag(&other)
+        .build()];
+
+    let subcommands = &[
+        SubCommand::build()
+            .with_long_name("build")
+            .with_flags(build_flags)
+            .with_command(&default_command)
+            .build(),
+        SubCommand::build()
+            .with_long_name("clean")
+            .with_flags(other_flags)
+            .with_command(&default_command)
+            .build(),
+    ];
+
+    let app: Application = Application::build()
+        .with_flags(flags)
+        .with_parameters(parameters)
+        .with_subcommands(subcommands)
+        .with_binder(bind)
+        .build();
+
+    execute_captured(&app, &["--verbose", "build", "-f"])
+        .result
+        .unwrap();
+
+    let bound = std::mem::take(&mut *BOUND.lock().unwrap());
+    assert_eq!(bound.len(), 3);
+    assert_eq!(
+        bound.into_iter().collect::<HashMap<_, _>>(),
+        HashMap::from([
+            ("verbose".to_string(), ArgValue::Flag(true)),
+            ("output".to_string(), ArgValue::Param(None)),
+            ("f".to_string(), ArgValue::Flag(true)),
+        ])
+    );
+
+    // nothing is bound when parsing fails or help is shown
+    assert!(execute_captured(&app, &["--unknown"]).result.is_err());
+    execute_captured(&app, &["--help"]).result.unwrap();
+    assert!(BOUND.lock().unwrap().is_empty());
+}
@@ -0,0 +1,79 @@
+/*
+ * Copyright 2023 Jacob R. Green
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::CommandLineError;
+
+/// Splits a command line into arguments, the way a POSIX shell would.
+///
+/// Arguments are separated by whitespace. Single quotes keep everything up to the closing quote verbatim,
+/// double quotes do too except that `\"` and `\\` are unescaped, and outside of quotes a backslash escapes the
+/// following character.
+pub(super) fn tokenize(line: &str) -> Result<Vec<String>, CommandLineError> {
+    let mut tokens = Vec::new();
+    // the argument being built, `None` between arguments
+    let mut token: Option<String> = None;
+    let mut chars = line.chars();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            ch if ch.is_whitespace() => tokens.extend(token.take()),
+            '\'' => {
+                let token = token.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(ch) => token.push(ch),
+                        None => return Err(unterminated('\'')),
+                    }
+                }
+            }
+            '"' => {
+                let token = token.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(ch @ ('"' | '\\')) => token.push(ch),
+                            Some(ch) => {
+                                token.push('\\');
+                                token.push(ch);
+                            }
+                            None => return Err(unterminated('"')),
+                        },
+                        Some(ch) => token.push(ch),
+                        None => return Err(unterminated('"')),
+                    }
+                }
+            }
+            '\\' => match chars.next() {
+                Some(ch) => token.get_or_insert_with(String::new).push(ch),
+                None => {
+                    return Err(CommandLineError::ParseError(
+                        "Trailing backslash".to_string(),
+                    ))
+                }
+            },
+            ch => token.get_or_insert_with(String::new).push(ch),
+        }
+    }
+
+    tokens.extend(token);
+    Ok(tokens)
+}
+
+fn unterminated(quote: char) -> CommandLineError {
+    CommandLineError::ParseError(format!("Unterminated {} quote", quote))
+}
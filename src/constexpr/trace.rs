@@ -0,0 +1,83 @@
+/*
+ * Copyright 2023 Jacob R. Green
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::ParseEvent;
+
+#[cfg(feature = "std")]
+use super::io::{self, Write};
+#[cfg(not(feature = "std"))]
+use alloc::{
+    format,
+    string::{String, ToString},
+};
+use core::fmt::Write as _;
+
+/// A writer that receives every parse event as a line of JSON, see
+/// [`with_json_trace`](super::builders::ApplicationBuilder::with_json_trace).
+#[cfg(feature = "std")]
+pub(super) type JsonTraceWriter = std::sync::Mutex<dyn Write + Send>;
+
+impl ParseEvent<'_> {
+    /// The event as a single line JSON object, its kind under `event` (e.g.
+    /// `{"event":"FlagMarked","name":"verbose"}`).
+    pub fn to_json(&self) -> String {
+        match self {
+            ParseEvent::FlagMarked(name) => {
+                format!(r#"{{"event":"FlagMarked","name":{}}}"#, json_string(name))
+            }
+            ParseEvent::ParameterSet { name, value } => format!(
+                r#"{{"event":"ParameterSet","name":{},"value":{}}}"#,
+                json_string(name),
+                json_string(value)
+            ),
+            ParseEvent::SubcommandDispatched(name) => format!(
+                r#"{{"event":"SubcommandDispatched","name":{}}}"#,
+                json_string(name)
+            ),
+            ParseEvent::Error(err) => format!(
+                r#"{{"event":"Error","message":{}}}"#,
+                json_string(&err.to_string())
+            ),
+        }
+    }
+}
+
+/// Writes `event` to `w` as a line of JSON.
+#[cfg(feature = "std")]
+pub(super) fn write_json(w: &mut dyn Write, event: &ParseEvent) -> io::Result<()> {
+    writeln!(w, "{}", event.to_json())
+}
+
+/// Quotes `s` as a JSON string, escaping quotes, backslashes and control characters.
+fn json_string(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(quoted, "\\u{:04x}", c as u32);
+            }
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
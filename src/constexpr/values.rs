@@ -0,0 +1,145 @@
+/*
+ * Copyright 2023 Jacob R. Green
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::*;
+
+/// The key positional arguments are collected under by [`collect_values`](Application::collect_values).
+pub const POSITIONALS_KEY: &str = "ARGS";
+
+/// A snapshot of a single argument's value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ArgValue {
+    /// Whether a flag was present.
+    Flag(bool),
+    /// The value of a parameter, if it was given.
+    Param(Option<String>),
+    /// A list of values, such as the positional arguments.
+    Multi(Vec<String>),
+}
+
+/// Reads every flag and parameter of a command, keyed by long name (or short name if it has none).
+pub(super) fn collect_values<R, C: Command<R> + ?Sized>(command: &C) -> Map<String, ArgValue> {
+    let mut values = Map::new();
+
+    for flag in command.flags() {
+        values.insert(
+            flag.key_name().to_string(),
+            ArgValue::Flag(flag.is_present()),
+        );
+    }
+
+    for param in command.parameters() {
+        values.insert(param.key_name().to_string(), param_value(param));
+    }
+
+    if let Some(positionals) = command.positionals() {
+        values.insert(
+            POSITIONALS_KEY.to_string(),
+            ArgValue::Multi(positionals.values().to_vec()),
+        );
+    }
+
+    values
+}
+
+/// Passes the value of every flag and parameter of the application and of the subcommands in `path` to `binder`,
+/// keyed by long name (or short name if it has none), parents first and in declaration order.
+pub(super) fn bind<R>(app: &Application<R>, path: &[String], binder: Binder) {
+    bind_command(app, binder);
+    let mut subcommands = app.subcommands();
+    for name in path {
+        let Some(command) = subcommands
+            .iter()
+            .find(|command| command.long_name() == name)
+        else {
+            break;
+        };
+        bind_command(command, binder);
+        subcommands = Command::subcommands(command);
+    }
+}
+
+fn bind_command<R, C: Command<R> + ?Sized>(command: &C, binder: Binder) {
+    for flag in command.flags() {
+        binder(flag.key_name(), ArgValue::Flag(flag.is_present()));
+    }
+    for param in command.parameters() {
+        binder(param.key_name(), param_value(param));
+    }
+}
+
+/// Reads the current value of a parameter.
+fn param_value(param: &Parameter) -> ArgValue {
+    if let Some(entries) = param.entries() {
+        ArgValue::Multi(
+            entries
+                .iter()
+                .map(|(key, value)| format!("{}={}", key, value))
+                .collect(),
+        )
+    } else if let Some(values) = param.values() {
+        ArgValue::Multi(values.to_vec())
+    } else if let Some(number) = param.number() {
+        ArgValue::Param(Some(number.to_string()))
+    } else {
+        ArgValue::Param(param.value().map(str::to_string))
+    }
+}
+
+/// Describes the current value of every flag and parameter, one per line (e.g. `--verbose: false`).
+pub(super) fn config_lines(flags: &[Flag], params: &[Parameter]) -> Vec<String> {
+    let flags = flags
+        .iter()
+        .map(|flag| (flag.display_name(), ArgValue::Flag(flag.is_present())));
+    let params = params
+        .iter()
+        .map(|param| (param.display_name(), param_value(param)));
+
+    flags
+        .chain(params)
+        .map(|(name, value)| match value {
+            ArgValue::Flag(present) => format!("{}: {}", name, present),
+            ArgValue::Param(Some(value)) => format!("{}: {}", name, value),
+            ArgValue::Param(None) => format!("{}: <unset>", name),
+            ArgValue::Multi(values) => format!("{}: [{}]", name, values.join(", ")),
+        })
+        .collect()
+}
+
+/// Describes the current value of every flag and parameter of a command for debugging, one per line, keyed by long
+/// name (or short name if it has none) in declaration order (e.g. `verbose: false` or `output: None`).
+pub(super) fn debug_state<R, C: Command<R> + ?Sized>(command: &C) -> String {
+    let flags = command
+        .flags()
+        .iter()
+        .map(|flag| (flag.key_name(), ArgValue::Flag(flag.is_present())));
+    let params = command
+        .parameters()
+        .iter()
+        .map(|param| (param.key_name(), param_value(param)));
+
+    let mut state = String::new();
+    for (name, value) in flags.chain(params) {
+        let value = match value {
+            ArgValue::Flag(present) => present.to_string(),
+            ArgValue::Param(Some(value)) => value,
+            ArgValue::Param(None) => "None".to_string(),
+            ArgValue::Multi(values) => format!("[{}]", values.join(", ")),
+        };
+        state.push_str(&format!("{}: {}\n", name, value));
+    }
+    state
+}
@@ -21,5 +21,11 @@
 //!
 //! Apache 2.0. See [LICENSE](https://raw.githubusercontent.com/jacobrgreen114/cliutil-rs/main/LICENSE) file.
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
 /// Api for creating a compile-time static command line application.
 pub mod constexpr;
+
+pub mod prelude;
@@ -0,0 +1,53 @@
+/*
+ * Copyright 2023 Jacob R. Green
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Re-exports of the commonly used types.
+//!
+//! # Example
+//! ```rust
+//! use cliutil::prelude::*;
+//!
+//! static VERBOSE: FlagValue = FlagValue::new();
+//!
+//! static FLAGS: &[Flag] = &[Flag::build()
+//!     .with_short_name("v")
+//!     .with_long_name("verbose")
+//!     .with_description("Prints more output")
+//!     .with_flag(&VERBOSE)
+//!     .build()];
+//!
+//! static APPLICATION: Application = Application::build()
+//!     .with_name("app")
+//!     .with_flags(FLAGS)
+//!     .with_command(&app_main)
+//!     .build();
+//!
+//! fn app_main() {}
+//!
+//! fn main() -> Result<(), CommandLineError> {
+//!     let (mut out, mut err) = (Vec::new(), Vec::new());
+//!     APPLICATION.execute_into(["-v"].iter(), &mut out, &mut err)?;
+//!     assert!(VERBOSE.value());
+//!     Ok(())
+//! }
+//! ```
+
+pub use crate::constexpr::builders::*;
+pub use crate::constexpr::{
+    Application, ArgValue, BuildError, ColorChoice, CommandLineError, Flag, FlagValue, HelpLabels,
+    HelpOptions, HelpStyle, ModeValue, Parameter, ParameterValue, PositionalValue, RunOutcome,
+    SubCommand,
+};